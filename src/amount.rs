@@ -0,0 +1,85 @@
+use anyhow::{anyhow, Result};
+
+use crate::transaction::TransactionOptions;
+
+/// Converts a UI amount such as `1.5` into base units of a mint with `decimals`.
+///
+/// The conversion is exact: amounts with more fractional digits than the mint
+/// supports and amounts that overflow `u64` are rejected.
+pub fn ui_amount_to_base_units(amount: &str, decimals: u8) -> Result<u64> {
+    let amount = amount.trim();
+
+    let (whole, fraction) = match amount.split_once('.') {
+        Some((whole, fraction)) => (whole, fraction),
+        None => (amount, ""),
+    };
+
+    if (whole.is_empty() && fraction.is_empty())
+        || !whole.chars().all(|x| x.is_ascii_digit())
+        || !fraction.chars().all(|x| x.is_ascii_digit())
+    {
+        return Err(anyhow!("Invalid amount '{amount}'"));
+    }
+
+    if fraction.len() > decimals as usize {
+        return Err(anyhow!(
+            "Amount '{amount}' has more than {decimals} decimal place(s)"
+        ));
+    }
+
+    let overflow = || anyhow!("Amount '{amount}' is too large");
+
+    let whole: u64 = if whole.is_empty() {
+        0
+    } else {
+        whole.parse().map_err(|_| overflow())?
+    };
+    let fraction = format!("{fraction:0<width$}", width = decimals as usize);
+    let fraction: u64 = if fraction.is_empty() {
+        0
+    } else {
+        fraction
+            .parse()
+            .map_err(|_| anyhow!("Invalid amount '{amount}'"))?
+    };
+
+    10u64
+        .checked_pow(decimals as u32)
+        .and_then(|x| whole.checked_mul(x))
+        .and_then(|x| x.checked_add(fraction))
+        .ok_or_else(overflow)
+}
+
+/// Formats base units of a mint with `decimals` as a UI amount, e.g. `1500000` with 6 decimals as `1.5`.
+pub fn base_units_to_ui_amount(amount: u64, decimals: u8) -> String {
+    if decimals == 0 {
+        return amount.to_string();
+    }
+
+    let amount = format!("{amount:0>width$}", width = decimals as usize + 1);
+    let (whole, fraction) = amount.split_at(amount.len() - decimals as usize);
+    let fraction = fraction.trim_end_matches('0');
+
+    if fraction.is_empty() {
+        whole.to_string()
+    } else {
+        format!("{whole}.{fraction}")
+    }
+}
+
+/// Converts the UI amount given for `name` and echoes the UI and raw values.
+pub fn parse_and_echo_ui_amount(
+    name: &str,
+    amount: &str,
+    decimals: u8,
+    options: &TransactionOptions,
+) -> Result<u64> {
+    let raw = ui_amount_to_base_units(amount, decimals)?;
+
+    options.progress(format!(
+        "{name}: {} ({raw} base units, {decimals} decimals)",
+        base_units_to_ui_amount(raw, decimals)
+    ));
+
+    Ok(raw)
+}
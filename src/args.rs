@@ -4,12 +4,17 @@ use clap::{Parser, Subcommand};
 
 use solana_program::pubkey::Pubkey;
 
-use crate::transaction::{Filter, Priority};
+use crate::{
+    filter::Filter,
+    multisig::MultisigOptions,
+    output::OutputFormat,
+    transaction::{Priority, TransactionOptions},
+};
 
 #[derive(Parser)]
 #[clap(author, version, about)]
 pub struct Args {
-    /// Path to the keypair file.
+    /// Path to the keypair file, or its public key with --sign-only.
     #[arg(short, long, global = true)]
     pub keypair_path: Option<PathBuf>,
 
@@ -17,6 +22,9 @@ pub struct Args {
     #[arg(short, long, global = true)]
     pub rpc_url: Option<String>,
 
+    #[command(flatten)]
+    pub transaction: TransactionOptions,
+
     #[clap(subcommand)]
     pub command: Commands,
 }
@@ -26,9 +34,9 @@ pub enum Commands {
    
     /// Create a mule
     Create {
-        /// base swap rate to use for swapping NFTs to SPL tokens. Do not forget decimals 
+        /// base swap rate to use for swapping NFTs to SPL tokens, in UI units of the fungible mint (e.g. 1000.5)
         #[arg(long)]
-        base_swap_rate: u64,
+        base_swap_rate: String,
 
         /// auto-generate swap markers on swap as long as the NFT matches the filter.
         #[arg(long)]
@@ -45,6 +53,7 @@ pub enum Commands {
         #[arg(long)]
         fungible_mint: Pubkey,
 
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
         #[arg(short = 'P', long, default_value = "low")]
         priority: Priority,
 
@@ -54,14 +63,207 @@ pub enum Commands {
         fee_rate_per_swap_basis_points: Option<u16>,
         #[arg(long)]
         swap_fee_treasury: Option<Pubkey>,
+        /// SPL fee per swap, in UI units of the fungible mint
         #[arg(long)]
-        fee_per_swap_spl_amount: Option<u64>,
+        fee_per_swap_spl_amount: Option<String>,
         #[arg(long)]
         burn_spl_basis_points: Option<u16>,
         #[arg(long)]
         name: String,
-        
 
+        /// Seed used to derive the mule address. A random seed is generated when omitted
+        #[arg(long, conflicts_with = "seed_keypair")]
+        seed: Option<Pubkey>,
+
+        /// Keypair file whose public key is used as the seed
+        #[arg(long)]
+        seed_keypair: Option<PathBuf>,
+
+        #[command(flatten)]
+        multisig: MultisigOptions,
+    },
+    /// Update the parameters of a mule. Only the given fields are changed
+    Update {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// base swap rate to use for swapping NFTs to SPL tokens, in UI units of the fungible mint (e.g. 1000.5)
+        #[arg(long)]
+        base_swap_rate: Option<String>,
+
+        /// auto-generate swap markers on swap as long as the NFT matches the filter (true / false)
+        #[arg(long)]
+        auto_generate_swap_marker: Option<bool>,
+
+        #[arg(long)]
+        update_auth: Option<Pubkey>,
+
+        /// Filter that defines what assets are swappable in this mule deployment
+        #[arg(long)]
+        filter: Option<Filter>,
+
+        #[arg(long)]
+        fee_per_swap_lamports: Option<u64>,
+        #[arg(long)]
+        fee_rate_per_swap_basis_points: Option<u16>,
+        #[arg(long)]
+        swap_fee_treasury: Option<Pubkey>,
+        /// SPL fee per swap, in UI units of the fungible mint
+        #[arg(long)]
+        fee_per_swap_spl_amount: Option<String>,
+        #[arg(long)]
+        burn_spl_basis_points: Option<u16>,
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Clear the update authority, leaving updates to the mule authority
+        #[arg(long, conflicts_with = "update_auth")]
+        clear_update_auth: bool,
+
+        /// Remove the lamport fee per swap
+        #[arg(long, conflicts_with = "fee_per_swap_lamports")]
+        clear_fee_per_swap_lamports: bool,
+
+        /// Remove the fee rate per swap
+        #[arg(long, conflicts_with = "fee_rate_per_swap_basis_points")]
+        clear_fee_rate_per_swap_basis_points: bool,
+
+        /// Remove the swap fee treasury
+        #[arg(long, conflicts_with = "swap_fee_treasury")]
+        clear_swap_fee_treasury: bool,
+
+        /// Remove the SPL fee per swap
+        #[arg(long, conflicts_with = "fee_per_swap_spl_amount")]
+        clear_fee_per_swap_spl_amount: bool,
+
+        /// Stop burning SPL on swaps
+        #[arg(long, conflicts_with = "burn_spl_basis_points")]
+        clear_burn_spl_basis_points: bool,
+
+        /// Skip the confirmation prompt
+        #[arg(short, long)]
+        yes: bool,
+
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
+        #[arg(short = 'P', long, default_value = "low")]
+        priority: Priority,
+
+        #[command(flatten)]
+        multisig: MultisigOptions,
+    },
+    /// Derive a mule address from its seed without touching the network
+    DeriveAddress {
+        /// Seed of the mule
+        #[arg(long, conflicts_with = "seed_keypair")]
+        seed: Option<Pubkey>,
+
+        /// Keypair file whose public key is the seed of the mule
+        #[arg(long)]
+        seed_keypair: Option<PathBuf>,
+    },
+    /// Show a decoded mule deployment
+    Show {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+    /// List mule deployments
+    List {
+        /// Only list mules created by this authority
+        #[arg(long)]
+        authority: Option<Pubkey>,
+
+        /// Only list mules swapping into this fungible mint
+        #[arg(long)]
+        fungible_mint: Option<Pubkey>,
+
+        /// Only list mules with this filter type: 'a' (all), 'g' (group) or 'c' (creator)
+        #[arg(long, value_parser = ["a", "g", "c"])]
+        filter_type: Option<String>,
+
+        /// Only list mules whose name contains this text (case insensitive)
+        #[arg(long)]
+        name: Option<String>,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+    /// Inspect swap markers of a mule deployment
+    SwapMarker {
+        #[clap(subcommand)]
+        command: SwapMarkerCommands,
+    },
+    /// List the NFTs of a wallet that a mule accepts
+    Eligible {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// Wallet to scan. Defaults to the keypair's wallet
+        #[arg(long)]
+        owner: Option<Pubkey>,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+    /// Deposit fungible liquidity into a mule
+    Fund {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// Amount to deposit in UI units, e.g. 1.5
+        #[arg(long)]
+        amount: String,
+
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
+        #[arg(short = 'P', long, default_value = "low")]
+        priority: Priority,
+    },
+    /// Report the liquidity and health of a mule
+    Status {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+    /// Manage address lookup tables used to compile v0 swap transactions
+    Lut {
+        #[clap(subcommand)]
+        command: LutCommands,
+    },
+    /// Manage durable nonce accounts used to sign transactions that do not expire
+    Nonce {
+        #[clap(subcommand)]
+        command: NonceCommands,
+    },
+    /// Add a signature to a transaction exported with --sign-only. Needs no RPC
+    Sign {
+        /// File holding the transaction exported with --sign-only
+        file: PathBuf,
+
+        /// Signature collected elsewhere, as PUBKEY=SIGNATURE. Can be repeated
+        #[arg(long = "signer")]
+        signers: Vec<String>,
+
+        /// File to write the signed transaction to. Defaults to stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Send a fully signed transaction and rebroadcast it until it is confirmed
+    Broadcast {
+        /// File holding the signed transaction
+        file: PathBuf,
     },
     /// Swap NFT to fungible under a given mule deployment
     SwapToFungible {
@@ -73,14 +275,162 @@ pub enum Commands {
         #[arg(long)]
         non_fungible_asset: Pubkey,
 
+        /// The asset group. Defaults to the collection of Core assets
         #[arg(long)]
         asset_group: Option<Pubkey>,
-        
-        /// The NFT source token account. Required for mints only
+
+        /// The NFT source token account, for mints only. Defaults to the wallet's token account holding the mint
         #[arg(long)]
         non_fungible_source_token_account: Option<Pubkey>,
 
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
+        #[arg(short = 'P', long, default_value = "low")]
+        priority: Priority,
+    },
+    /// Swap many NFTs to fungible under a given mule deployment
+    BatchSwapToFungible {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// CSV or JSON file listing the assets to swap
+        #[arg(long, conflicts_with = "all_eligible")]
+        assets_file: Option<PathBuf>,
+
+        /// Swap every NFT in the wallet that the mule accepts
+        #[arg(long)]
+        all_eligible: bool,
+
+        /// Number of transactions sent at the same time
+        #[arg(long, default_value_t = 4)]
+        concurrency: usize,
+
+        /// Path of the per-asset JSON report
+        #[arg(long, default_value = "batch-swap-report.json")]
+        report: PathBuf,
+
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
+        #[arg(short = 'P', long, default_value = "low")]
+        priority: Priority,
+    },
+    /// Swap fungible back to an escrowed NFT under a given mule deployment
+    SwapToNonFungible {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// The escrowed NFT key (mint / nifty asset / core asset)
+        #[arg(long)]
+        non_fungible_asset: Pubkey,
+
+        /// The asset group. Defaults to the collection of Core assets
+        #[arg(long)]
+        asset_group: Option<Pubkey>,
+
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
         #[arg(short = 'P', long, default_value = "low")]
         priority: Priority,
     }
 }
+
+#[derive(Clone, Subcommand)]
+pub enum SwapMarkerCommands {
+    /// Show the swap marker of an asset and whether the asset is escrowed in the mule
+    Show {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// The NFT key (mint / nifty asset / core asset)
+        #[arg(long)]
+        asset: Pubkey,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+    /// List all swap markers of a mule
+    List {
+        /// The mule deployment key
+        #[arg(long)]
+        mule: Pubkey,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+pub enum NonceCommands {
+    /// Create a durable nonce account
+    Create {
+        /// Keypair file of the new nonce account
+        #[arg(long)]
+        nonce_keypair: PathBuf,
+
+        /// Authority allowed to advance the nonce. Defaults to the keypair's wallet
+        #[arg(long)]
+        authority: Option<Pubkey>,
+
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
+        #[arg(short = 'P', long, default_value = "low")]
+        priority: Priority,
+    },
+    /// Show the authority and stored nonce of a nonce account
+    Show {
+        /// The nonce account key
+        #[arg(long)]
+        address: Pubkey,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+}
+
+#[derive(Clone, Subcommand)]
+pub enum LutCommands {
+    /// Create a lookup table holding the static swap programs and a mule's fixed accounts
+    Create {
+        /// The mule deployment key whose fixed accounts are added
+        #[arg(long)]
+        mule: Option<Pubkey>,
+
+        /// Additional address to add. Can be repeated
+        #[arg(long = "address")]
+        addresses: Vec<Pubkey>,
+
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
+        #[arg(short = 'P', long, default_value = "low")]
+        priority: Priority,
+    },
+    /// Add addresses to a lookup table. Addresses already in the table are skipped
+    Extend {
+        /// The lookup table key
+        #[arg(long)]
+        lookup_table: Pubkey,
+
+        /// The mule deployment key whose fixed accounts are added
+        #[arg(long)]
+        mule: Option<Pubkey>,
+
+        /// Additional address to add. Can be repeated
+        #[arg(long = "address")]
+        addresses: Vec<Pubkey>,
+
+        /// Priority fee: none, low, medium, high, max, auto or p<percentile> (e.g. p95)
+        #[arg(short = 'P', long, default_value = "low")]
+        priority: Priority,
+    },
+    /// Show the addresses of a lookup table
+    Show {
+        /// The lookup table key
+        #[arg(long)]
+        lookup_table: Pubkey,
+
+        /// Output format: table or json
+        #[arg(short, long, default_value = "table")]
+        output: OutputFormat,
+    },
+}
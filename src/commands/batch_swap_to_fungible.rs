@@ -0,0 +1,425 @@
+use std::{
+    collections::HashSet,
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+};
+
+use crate::{
+    setup::CliSigner,
+    transaction::{pack_instructions_with_prefix, MAX_CU},
+};
+
+use super::*;
+
+use indicatif::{ProgressBar, ProgressStyle};
+use solana_client::rpc_client::RpcClient;
+use solana_program::{instruction::Instruction, system_instruction};
+use solana_sdk::{address_lookup_table::AddressLookupTableAccount, signature::Signature};
+
+pub struct BatchSwapToFungibleArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    pub assets_file: Option<PathBuf>,
+    pub all_eligible: bool,
+    pub concurrency: usize,
+    pub report: PathBuf,
+    pub priority: Priority,
+    pub transaction: TransactionOptions,
+}
+
+/// An asset to swap, as read from the assets file or found in the wallet.
+#[derive(Debug, Clone)]
+pub struct BatchSwapEntry {
+    pub asset: Pubkey,
+    pub asset_group: Option<Pubkey>,
+    pub non_fungible_source_token_account: Option<Pubkey>,
+}
+
+#[derive(Debug, Deserialize)]
+struct BatchSwapEntryInput {
+    asset: String,
+    asset_group: Option<String>,
+    non_fungible_source_token_account: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BatchSwapStatus {
+    Pending,
+    Swapped,
+    Simulated,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct BatchSwapResult {
+    pub asset: String,
+    pub status: BatchSwapStatus,
+    pub signature: Option<String>,
+    pub error: Option<String>,
+}
+
+fn parse_optional_pubkey(value: Option<&str>) -> Result<Option<Pubkey>> {
+    match value.map(str::trim) {
+        None | Some("") => Ok(None),
+        Some(x) => Ok(Some(
+            Pubkey::from_str(x).map_err(|_| anyhow!("Invalid pubkey '{x}'"))?,
+        )),
+    }
+}
+
+/// Reads the assets to swap from a JSON array of
+/// `{ "asset", "asset_group"?, "non_fungible_source_token_account"? }` objects or from a
+/// CSV file with `asset[,asset_group[,non_fungible_source_token_account]]` rows.
+pub fn read_batch_swap_entries(path: &PathBuf) -> Result<Vec<BatchSwapEntry>> {
+    let is_json = path
+        .extension()
+        .map(|x| x.eq_ignore_ascii_case("json"))
+        .unwrap_or(false);
+
+    if is_json {
+        let inputs: Vec<BatchSwapEntryInput> = serde_json::from_reader(File::open(path)?)?;
+
+        return inputs
+            .into_iter()
+            .map(|input| {
+                Ok(BatchSwapEntry {
+                    asset: parse_optional_pubkey(Some(&input.asset))?
+                        .ok_or_else(|| anyhow!("Missing asset in {}", path.display()))?,
+                    asset_group: parse_optional_pubkey(input.asset_group.as_deref())?,
+                    non_fungible_source_token_account: parse_optional_pubkey(
+                        input.non_fungible_source_token_account.as_deref(),
+                    )?,
+                })
+            })
+            .collect();
+    }
+
+    let mut entries = vec![];
+
+    for (i, line) in std::fs::read_to_string(path)?.lines().enumerate() {
+        let line = line.trim();
+
+        // skip blank lines, comments and the header row
+        if line.is_empty() || line.starts_with('#') || (i == 0 && line.starts_with("asset")) {
+            continue;
+        }
+
+        let mut columns = line.split(',');
+        let asset = parse_optional_pubkey(columns.next())?
+            .ok_or_else(|| anyhow!("Missing asset on line {} of {}", i + 1, path.display()))?;
+
+        entries.push(BatchSwapEntry {
+            asset,
+            asset_group: parse_optional_pubkey(columns.next())?,
+            non_fungible_source_token_account: parse_optional_pubkey(columns.next())?,
+        });
+    }
+
+    Ok(entries)
+}
+
+/// Splits `ix_entries`, the entry of each packed instruction, along `batches`.
+pub fn batch_entries(batches: &[Vec<Instruction>], ix_entries: &[usize]) -> Vec<Vec<usize>> {
+    let mut offset = 0;
+
+    batches
+        .iter()
+        .map(|batch| {
+            let entries = ix_entries[offset..offset + batch.len()].to_vec();
+            offset += batch.len();
+            entries
+        })
+        .collect()
+}
+
+/// Sends the swaps `ixs` of `entries` in one transaction, or simulates it on dry runs.
+///
+/// When the transaction cannot be built or simulated, e.g. because the swaps
+/// exceed the compute unit cap together, it is split in halves and retried
+/// until single swaps fail on their own. Returns the outcome of each
+/// transaction with the entries it carried.
+///
+/// Output goes around `pb`, which other workers keep drawing.
+#[allow(clippy::too_many_arguments)]
+fn swap_batch(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    ixs: &[Instruction],
+    entries: &[usize],
+    lookup_tables: &[AddressLookupTableAccount],
+    priority: &Priority,
+    options: &TransactionOptions,
+    pb: &ProgressBar,
+) -> Vec<(Vec<usize>, Result<Option<Signature>>)> {
+    let prepared = with_compute_budget(
+        client,
+        signers,
+        ixs.to_vec(),
+        lookup_tables,
+        priority,
+        options,
+    )
+    .and_then(|instructions| {
+        if options.dry_run {
+            pb.suspend(|| simulate_tx(client, signers, &instructions, lookup_tables))?;
+        }
+        Ok(instructions)
+    });
+
+    match prepared {
+        Err(_) if ixs.len() > 1 => {
+            let mid = ixs.len() / 2;
+
+            let mut outcomes = swap_batch(
+                client,
+                signers,
+                &ixs[..mid],
+                &entries[..mid],
+                lookup_tables,
+                priority,
+                options,
+                pb,
+            );
+            outcomes.extend(swap_batch(
+                client,
+                signers,
+                &ixs[mid..],
+                &entries[mid..],
+                lookup_tables,
+                priority,
+                options,
+                pb,
+            ));
+
+            outcomes
+        }
+        Err(e) => vec![(entries.to_vec(), Err(e))],
+        Ok(_) if options.dry_run => vec![(entries.to_vec(), Ok(None))],
+        Ok(instructions) => {
+            let outcome = send_and_confirm_tx_with_resend(
+                client,
+                signers,
+                &instructions,
+                lookup_tables,
+                options,
+                |line| pb.println(line),
+            )
+            .map(Some);
+
+            vec![(entries.to_vec(), outcome)]
+        }
+    }
+}
+
+pub fn handle_batch_swap_to_fungible(args: BatchSwapToFungibleArgs) -> Result<()> {
+    if args.transaction.sign_only {
+        return Err(anyhow!(
+            "--sign-only exports a single transaction. Use swap-to-fungible for each asset instead"
+        ));
+    }
+
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let authority_sk = config.keypair;
+
+    let authority = authority_sk.pubkey();
+
+    let mule = MuleContext::fetch(&config.client, &args.mule)?;
+
+    let lookup_tables = fetch_lookup_tables(&config.client, &args.transaction.lookup_tables)?;
+
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+    let signers = transaction_signers(&authority_sk, nonce_authority.as_deref());
+
+    let entries = match (&args.assets_file, args.all_eligible) {
+        (Some(path), false) => read_batch_swap_entries(path)?,
+        (None, true) => get_eligible_non_fungibles(&config.client, &mule, &authority)?
+            .into_iter()
+            .map(|(x, _)| BatchSwapEntry {
+                asset: x.asset,
+                asset_group: None,
+                non_fungible_source_token_account: x.token_account,
+            })
+            .collect(),
+        _ => {
+            return Err(anyhow!(
+                "Exactly one of --assets-file or --all-eligible is required"
+            ))
+        }
+    };
+
+    // a second swap of the same asset would fail the whole transaction it is packed in
+    let mut seen = HashSet::new();
+    let entries: Vec<BatchSwapEntry> = entries
+        .into_iter()
+        .filter(|x| {
+            let first = seen.insert(x.asset);
+            if !first {
+                println!("Skipping duplicate asset {}", x.asset);
+            }
+            first
+        })
+        .collect();
+
+    if entries.is_empty() {
+        println!("No assets to swap");
+        return Ok(());
+    }
+
+    let mut results: Vec<BatchSwapResult> = entries
+        .iter()
+        .map(|x| BatchSwapResult {
+            asset: x.asset.to_string(),
+            status: BatchSwapStatus::Pending,
+            signature: None,
+            error: None,
+        })
+        .collect();
+
+    // build one swap per asset, remembering which entry each instruction belongs to
+    let mut ixs: Vec<Instruction> = vec![];
+    let mut ix_entries: Vec<usize> = vec![];
+
+    for (i, entry) in entries.iter().enumerate() {
+        match get_swap_to_fungible_ix(
+            &config.client,
+            &authority,
+            &mule,
+            &entry.asset,
+            entry.asset_group,
+            entry.non_fungible_source_token_account,
+        ) {
+            Ok(ix) => {
+                ixs.push(ix);
+                ix_entries.push(i);
+            }
+            Err(e) => {
+                results[i].status = BatchSwapStatus::Failed;
+                results[i].error = Some(e.to_string());
+            }
+        }
+    }
+
+    // reserve room for the nonce advance and compute budget instructions added when sending
+    let mut prefix = vec![];
+    if let Some(nonce_account) = &args.transaction.nonce_account {
+        let nonce_authority = nonce_authority
+            .as_deref()
+            .map(|x| x.pubkey())
+            .unwrap_or(authority);
+
+        prefix.push(system_instruction::advance_nonce_account(
+            nonce_account,
+            &nonce_authority,
+        ));
+    }
+    prefix.extend([
+        ComputeBudgetInstruction::set_compute_unit_limit(MAX_CU),
+        ComputeBudgetInstruction::set_compute_unit_price(0),
+    ]);
+    let batches = pack_instructions_with_prefix(
+        signers.len() as u32,
+        &authority,
+        &prefix,
+        &ixs,
+        &lookup_tables,
+    );
+
+    let batch_entries = batch_entries(&batches, &ix_entries);
+
+    println!(
+        "Swapping {} asset(s) in {} transaction(s)",
+        ixs.len(),
+        batches.len()
+    );
+
+    let pb = ProgressBar::new(batches.len() as u64);
+    pb.set_style(
+        ProgressStyle::default_bar()
+            .template("[{elapsed_precise}] {bar:40.cyan/blue} {pos}/{len} {msg}")?,
+    );
+
+    // simulation reports are printed, so keep them in order, and a durable nonce
+    // can only be used by one transaction at a time
+    let concurrency = if args.transaction.dry_run || args.transaction.nonce_account.is_some() {
+        1
+    } else {
+        args.concurrency.max(1)
+    };
+
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(results);
+
+    thread::scope(|scope| {
+        for _ in 0..concurrency.min(batches.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::SeqCst);
+                let Some(batch) = batches.get(i) else {
+                    break;
+                };
+
+                let outcomes = swap_batch(
+                    &config.client,
+                    &signers,
+                    batch,
+                    &batch_entries[i],
+                    &lookup_tables,
+                    &args.priority,
+                    &args.transaction,
+                    &pb,
+                );
+
+                let mut results = results.lock().unwrap();
+                for (entries, outcome) in &outcomes {
+                    for entry in entries {
+                        let result = &mut results[*entry];
+                        match outcome {
+                            Ok(Some(sig)) => {
+                                result.status = BatchSwapStatus::Swapped;
+                                result.signature = Some(sig.to_string());
+                            }
+                            Ok(None) => result.status = BatchSwapStatus::Simulated,
+                            Err(e) => {
+                                result.status = BatchSwapStatus::Failed;
+                                result.error = Some(e.to_string());
+                            }
+                        }
+                    }
+                }
+                drop(results);
+
+                pb.inc(1);
+            });
+        }
+    });
+
+    pb.finish_and_clear();
+
+    let results = results.into_inner().unwrap();
+
+    serde_json::to_writer_pretty(File::create(&args.report)?, &results)?;
+
+    let failed = results
+        .iter()
+        .filter(|x| x.status == BatchSwapStatus::Failed)
+        .count();
+
+    println!(
+        "{} of {} asset(s) processed successfully. Report written to {}",
+        results.len() - failed,
+        results.len(),
+        args.report.display()
+    );
+
+    if failed > 0 {
+        return Err(anyhow!("{failed} swap(s) failed"));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use crate::{
+    offline::{absent_signers, message_programs, TransactionEnvelope},
+    transaction::{broadcast_until_landed, get_nonce_data},
+};
+
+use super::*;
+
+pub struct BroadcastArgs {
+    pub rpc_url: Option<String>,
+    /// Fully signed envelope
+    pub file: PathBuf,
+    pub transaction: TransactionOptions,
+}
+
+pub fn handle_broadcast(args: BroadcastArgs) -> Result<()> {
+    let client = CliConfig::builder(None, args.rpc_url).build_client()?;
+
+    let envelope = TransactionEnvelope::read(&args.file)?;
+    let tx = envelope.transaction()?;
+
+    let absent = absent_signers(&tx);
+    if !absent.is_empty() {
+        return Err(anyhow!(
+            "Missing signature(s) of {}. Add them with the sign command",
+            absent
+                .iter()
+                .map(|x| x.to_string())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+
+    if tx.verify_with_results().iter().any(|x| !x) {
+        return Err(anyhow!(
+            "Transaction has invalid signatures. Was it modified after signing?"
+        ));
+    }
+
+    let blockhash = *tx.message.recent_blockhash();
+    let nonce_account = envelope.nonce_account()?;
+
+    // a durable transaction can land until its nonce is advanced, any other until its blockhash expires
+    let is_expired = || match &nonce_account {
+        Some(nonce_account) => Ok(get_nonce_data(&client, nonce_account)?.blockhash() != blockhash),
+        None => Ok(!client.is_blockhash_valid(&blockhash, client.commitment())?),
+    };
+
+    if is_expired()? {
+        return Err(anyhow!(
+            "Blockhash {blockhash} has expired. Export the transaction again with --sign-only"
+        ));
+    }
+
+    println!("Broadcasting {}", tx.signatures[0]);
+
+    let ixs = message_programs(&tx.message);
+
+    match broadcast_until_landed(&client, &tx, &ixs, &args.transaction, is_expired)? {
+        Some(sig) => {
+            println!("Confirmed tx: {sig}");
+            Ok(())
+        }
+        None => Err(anyhow!(
+            "Transaction {} expired before it was confirmed",
+            tx.signatures[0]
+        )),
+    }
+}
@@ -1,11 +1,14 @@
 use libreplex_mule_client::{instructions::CreateMuleInstructionArgs, types::Filter};
 
+use crate::amount::parse_and_echo_ui_amount;
+
 use super::*;
 
 pub struct CreateArgs {
     pub keypair_path: Option<PathBuf>,
     pub rpc_url: Option<String>,
-    pub base_swap_rate: u64,
+    /// UI amount, e.g. 1000.5
+    pub base_swap_rate: String,
     pub auto_generate_swap_marker: bool,
     pub filter: Filter,
     pub fungible_mint: Pubkey,
@@ -14,12 +17,53 @@ pub struct CreateArgs {
     pub fee_per_swap_lamports: Option<u64>,
     pub fee_rate_per_swap_basis_points: Option<u16>,
     pub swap_fee_treasury: Option<Pubkey>,
-    pub fee_per_swap_spl_amount: Option<u64>,
+    /// UI amount
+    pub fee_per_swap_spl_amount: Option<String>,
     pub burn_spl_basis_points: Option<u16>,
     pub name: String,
+    pub seed: Option<Pubkey>,
+    pub seed_keypair: Option<PathBuf>,
+    pub transaction: TransactionOptions,
+    pub multisig: MultisigOptions,
+}
+
+pub struct DeriveAddressArgs {
+    pub seed: Option<Pubkey>,
+    pub seed_keypair: Option<PathBuf>,
+}
+
+pub fn get_mule_address(seed: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[b"mule", seed.as_ref()], &libreplex_mule_client::ID).0
+}
+
+/// Resolves the mule seed from `--seed` or `--seed-keypair`, if either was given.
+pub fn resolve_seed(seed: Option<Pubkey>, seed_keypair: Option<PathBuf>) -> Result<Option<Pubkey>> {
+    match (seed, seed_keypair) {
+        (Some(_), Some(_)) => Err(anyhow!("Only one of --seed and --seed-keypair can be used")),
+        (Some(seed), None) => Ok(Some(seed)),
+        (None, Some(path)) => {
+            let keypair = read_keypair_file(&path)
+                .map_err(|_| anyhow!("Unable to read seed keypair file {}", path.display()))?;
+            Ok(Some(keypair.pubkey()))
+        }
+        (None, None) => Ok(None),
+    }
+}
+
+pub fn handle_derive_address(args: DeriveAddressArgs) -> Result<()> {
+    let seed = resolve_seed(args.seed, args.seed_keypair)?
+        .ok_or_else(|| anyhow!("One of --seed or --seed-keypair is required"))?;
+
+    println!("Seed: {seed}");
+    println!("Mule: {}", get_mule_address(&seed));
+
+    Ok(())
 }
 
-pub fn handle_create(args: CreateArgs) -> Result<()> {
+pub fn handle_create(mut args: CreateArgs) -> Result<()> {
+    // stdout is kept for the instruction bundle
+    args.transaction.progress_to_stderr = args.multisig.prints_bundle();
+
     let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
 
   
@@ -28,33 +72,69 @@ pub fn handle_create(args: CreateArgs) -> Result<()> {
     let authority = authority_sk.pubkey();
 
 
-    let seed = Keypair::new();
+    let seed = match resolve_seed(args.seed, args.seed_keypair)? {
+        Some(seed) => seed,
+        None => {
+            let seed = Keypair::new().pubkey();
+            args.transaction.progress(format!(
+                "Generated seed {seed}. Pass it as --seed to re-derive this mule."
+            ));
+            seed
+        }
+    };
+
+    let mule = get_mule_address(&seed);
+
+    if let Ok(account) = config.client.get_account(&mule) {
+        if account.owner == libreplex_mule_client::ID {
+            args.transaction.progress(format!(
+                "Mule {mule} already exists for seed {seed}. Nothing to do."
+            ));
+            return Ok(());
+        }
+        return Err(anyhow!(
+            "Mule address {mule} is already in use by an account owned by {}",
+            account.owner
+        ));
+    }
 
-    let mule = Pubkey::find_program_address(
-        &[b"mule", seed.pubkey().as_ref()],
-        &libreplex_mule_client::ID,
-    )
-    .0;
+    let mint = MintInfo::fetch(&config.client, &args.fungible_mint)?;
+
+    let base_swap_rate = parse_and_echo_ui_amount(
+        "Base swap rate",
+        &args.base_swap_rate,
+        mint.decimals,
+        &args.transaction,
+    )?;
+    let fee_per_swap_spl_amount = args
+        .fee_per_swap_spl_amount
+        .map(|x| {
+            parse_and_echo_ui_amount("Fee per swap (SPL)", &x, mint.decimals, &args.transaction)
+        })
+        .transpose()?;
 
     let ix_args = CreateMuleInstructionArgs {
-        seed: seed.pubkey(),
-        base_swap_rate: args.base_swap_rate,
+        seed,
+        base_swap_rate,
         update_auth: args.update_auth,
         auto_generate_swap_marker: args.auto_generate_swap_marker,
         filter: args.filter,
         fee_per_swap_lamports: args.fee_per_swap_lamports,
         fee_rate_per_swap_basis_points: args.fee_rate_per_swap_basis_points,
         swap_fee_treasury: args.swap_fee_treasury,
-        fee_per_swap_spl_amount: args.fee_per_swap_spl_amount,
+        fee_per_swap_spl_amount,
         name: args.name,
         burn_spl_basis_points: args.burn_spl_basis_points,
     };
 
 
 
+    // with a multisig, its vault creates and pays for the mule
+    let mule_authority = args.multisig.authority().unwrap_or(authority);
+
     let ix = CreateMule {
-        payer: authority,
-        authority: authority,
+        payer: mule_authority,
+        authority: mule_authority,
         mule: mule,
         cosigner: None,
         fungible_asset: args.fungible_mint,
@@ -63,24 +143,36 @@ pub fn handle_create(args: CreateArgs) -> Result<()> {
     }
     .instruction(ix_args);
 
-    let signers = vec![&authority_sk];
-
-    let micro_lamports = get_priority_fee(&args.priority);
-    let compute_units = 500_000; //get_compute_units(&config.client, &[ix.clone()], &signers)?;
-
-    let instructions = vec![
-        ComputeBudgetInstruction::set_compute_unit_limit(compute_units as u32),
-        ComputeBudgetInstruction::set_compute_unit_price(micro_lamports),
-        ix,
-    ];
-
-    println!("Sending transaction");
-
-
-
-    let sig = send_and_confirm_tx_with_spinner(&config.client, &signers, &instructions)?;
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+    let signers = transaction_signers(&authority_sk, nonce_authority.as_deref());
+
+    let lookup_tables = fetch_lookup_tables(&config.client, &args.transaction.lookup_tables)?;
+
+    if mule_authority != authority {
+        return propose_admin_ixs(
+            &config.client,
+            &signers,
+            &[ix],
+            &lookup_tables,
+            &args.priority,
+            &args.transaction,
+            &args.multisig,
+            &format!("Create mule {mule}"),
+        );
+    }
 
-    println!("Mule {mule} created in tx: {sig}");
+    let sig = execute_ixs(
+        &config.client,
+        &signers,
+        vec![ix],
+        &lookup_tables,
+        &args.priority,
+        &args.transaction,
+    )?;
+
+    if let Some(sig) = sig {
+        println!("Mule {mule} created in tx: {sig}");
+    }
 
     Ok(())
 }
@@ -0,0 +1,101 @@
+use crate::{
+    amount::base_units_to_ui_amount,
+    filter::Filter,
+    output::{print_json, OutputFormat},
+};
+
+use super::*;
+
+use solana_client::rpc_client::RpcClient;
+
+pub struct EligibleArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    pub owner: Option<Pubkey>,
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct EligibleAsset {
+    pub asset: String,
+    pub kind: AssetKind,
+    pub token_account: Option<String>,
+    pub group: Option<String>,
+    /// Fungible amount received for the swap, in base units
+    pub amount: u64,
+}
+
+/// Returns the non-fungibles held by `owner` that pass the filter of `mule`.
+pub fn get_eligible_non_fungibles(
+    client: &RpcClient,
+    mule: &MuleContext,
+    owner: &Pubkey,
+) -> Result<Vec<(WalletAsset, ResolvedNonFungible)>> {
+    let filter = Filter::from(mule.mule.filter.clone());
+
+    let mut eligible = vec![];
+
+    for asset in get_wallet_non_fungibles(client, owner)? {
+        let resolved = match resolve_non_fungible(client, &asset.asset) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Skipping {}: {e}", asset.asset);
+                continue;
+            }
+        };
+
+        if filter.accepts(resolved.group, &resolved.verified_creators()) {
+            eligible.push((asset, resolved));
+        }
+    }
+
+    Ok(eligible)
+}
+
+pub fn handle_eligible(args: EligibleArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let owner = args.owner.unwrap_or_else(|| config.keypair.pubkey());
+
+    let mule = MuleContext::fetch(&config.client, &args.mule)?;
+    let quote = mule.quote_swap_to_fungible();
+
+    let eligible: Vec<EligibleAsset> = get_eligible_non_fungibles(&config.client, &mule, &owner)?
+        .into_iter()
+        .map(|(asset, resolved)| EligibleAsset {
+            asset: asset.asset.to_string(),
+            kind: asset.kind,
+            token_account: asset.token_account.map(|x| x.to_string()),
+            group: resolved.group.map(|x| x.to_string()),
+            amount: quote.net,
+        })
+        .collect();
+
+    match args.output {
+        OutputFormat::Table => {
+            let mint = MintInfo::fetch(&config.client, &mule.mule.fungible_asset)?;
+            let ui_amount = |amount: u64| base_units_to_ui_amount(amount, mint.decimals);
+
+            println!("{:<44}  {:<9}  Amount", "Asset", "Kind");
+            for asset in &eligible {
+                println!(
+                    "{:<44}  {:<9}  {}",
+                    asset.asset,
+                    format!("{:?}", asset.kind),
+                    ui_amount(asset.amount)
+                );
+            }
+            println!(
+                "{} eligible asset(s) for {owner}, {} fungible(s) in total (fee per swap: {} SPL, {} lamports)",
+                eligible.len(),
+                ui_amount(quote.net.saturating_mul(eligible.len() as u64)),
+                ui_amount(quote.fee),
+                quote.fee_lamports
+            );
+        }
+        OutputFormat::Json => print_json(&eligible)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::amount::{base_units_to_ui_amount, ui_amount_to_base_units};
+
+use super::*;
+
+use spl_associated_token_account::{
+    get_associated_token_address_with_program_id,
+    instruction::create_associated_token_account_idempotent,
+};
+
+pub struct FundArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    /// UI amount, e.g. 1.5
+    pub amount: String,
+    pub priority: Priority,
+    pub transaction: TransactionOptions,
+}
+
+pub fn handle_fund(args: FundArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let authority_sk = config.keypair;
+
+    let authority = authority_sk.pubkey();
+
+    let data = config.client.get_account_data(&args.mule)?;
+    let mule_obj = Mule::from_bytes(&data)?;
+
+    let mint = MintInfo::fetch(&config.client, &mule_obj.fungible_asset)?;
+
+    let amount = ui_amount_to_base_units(&args.amount, mint.decimals)?;
+
+    let source = get_associated_token_address_with_program_id(
+        &authority,
+        &mint.address,
+        &mint.token_program,
+    );
+    let destination = get_associated_token_address_with_program_id(
+        &args.mule,
+        &mint.address,
+        &mint.token_program,
+    );
+
+    let ixs = vec![
+        create_associated_token_account_idempotent(
+            &authority,
+            &args.mule,
+            &mint.address,
+            &mint.token_program,
+        ),
+        spl_token_2022::instruction::transfer_checked(
+            &mint.token_program,
+            &source,
+            &mint.address,
+            &destination,
+            &authority,
+            &[],
+            amount,
+            mint.decimals,
+        )?,
+    ];
+
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+    let signers = transaction_signers(&authority_sk, nonce_authority.as_deref());
+
+    let lookup_tables = fetch_lookup_tables(&config.client, &args.transaction.lookup_tables)?;
+
+    args.transaction.progress(format!(
+        "Transferring {} ({amount} base units) of {} to {destination}",
+        base_units_to_ui_amount(amount, mint.decimals),
+        mint.address
+    ));
+
+    let sig = execute_ixs(
+        &config.client,
+        &signers,
+        ixs,
+        &lookup_tables,
+        &args.priority,
+        &args.transaction,
+    )?;
+
+    if let Some(sig) = sig {
+        println!("Mule {} funded. Tx: {sig}", args.mule);
+    }
+
+    Ok(())
+}
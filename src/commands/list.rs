@@ -0,0 +1,77 @@
+use crate::output::{print_json, OutputFormat};
+
+use super::*;
+
+pub struct ListArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub authority: Option<Pubkey>,
+    pub fungible_mint: Option<Pubkey>,
+    /// Filter type prefix: 'a', 'g' or 'c'
+    pub filter_type: Option<String>,
+    pub name: Option<String>,
+    pub output: OutputFormat,
+}
+
+pub fn handle_list(args: ListArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let mut filters = vec![];
+
+    if let Some(authority) = &args.authority {
+        filters.push(pubkey_filter(MULE_AUTHORITY_OFFSET, authority));
+    }
+
+    if let Some(fungible_mint) = &args.fungible_mint {
+        filters.push(pubkey_filter(MULE_FUNGIBLE_ASSET_OFFSET, fungible_mint));
+    }
+
+    let accounts = get_mule_program_accounts(&config.client, "Mule", filters)?;
+
+    let name = args.name.map(|x| x.to_lowercase());
+
+    let mut mules = vec![];
+
+    for (address, account) in accounts {
+        let mule_obj = match Mule::from_bytes(&account.data) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Skipping {address}: unable to decode mule: {e}");
+                continue;
+            }
+        };
+
+        let view = MuleView::new(&address, &mule_obj);
+
+        if let Some(filter_type) = &args.filter_type {
+            if !view.filter.starts_with(filter_type.as_str()) {
+                continue;
+            }
+        }
+
+        if let Some(name) = &name {
+            if !view.name.to_lowercase().contains(name.as_str()) {
+                continue;
+            }
+        }
+
+        mules.push(view);
+    }
+
+    mules.sort_by(|a, b| a.name.cmp(&b.name));
+
+    match args.output {
+        OutputFormat::Table => {
+            for mule in &mules {
+                println!(
+                    "{}  {:<32}  {}  {:<48}  {}",
+                    mule.address, mule.name, mule.fungible_asset, mule.filter, mule.base_swap_rate
+                );
+            }
+            println!("Found {} mule(s)", mules.len());
+        }
+        OutputFormat::Json => print_json(&mules)?,
+    }
+
+    Ok(())
+}
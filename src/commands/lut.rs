@@ -0,0 +1,272 @@
+use crate::{
+    output::{print_json, print_table, OutputFormat},
+    setup::CliSigner,
+};
+
+use super::*;
+
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
+use solana_sdk::{
+    address_lookup_table::{
+        instruction::{create_lookup_table, extend_lookup_table},
+        state::AddressLookupTable,
+    },
+    commitment_config::CommitmentConfig,
+};
+
+/// Addresses added per extend transaction, keeping it well under the size limit.
+const EXTEND_CHUNK_SIZE: usize = 20;
+
+pub struct LutCreateArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Option<Pubkey>,
+    pub addresses: Vec<Pubkey>,
+    pub priority: Priority,
+    pub transaction: TransactionOptions,
+}
+
+pub struct LutExtendArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub lookup_table: Pubkey,
+    pub mule: Option<Pubkey>,
+    pub addresses: Vec<Pubkey>,
+    pub priority: Priority,
+    pub transaction: TransactionOptions,
+}
+
+pub struct LutShowArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub lookup_table: Pubkey,
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LookupTableView {
+    pub address: String,
+    pub authority: Option<String>,
+    /// Slot the table was deactivated in, if any
+    pub deactivation_slot: Option<u64>,
+    pub last_extended_slot: u64,
+    pub addresses: Vec<String>,
+}
+
+/// Collects the fixed accounts of `mule`, if given, followed by `addresses`, without duplicates.
+fn collect_addresses(
+    client: &RpcClient,
+    mule: Option<Pubkey>,
+    addresses: Vec<Pubkey>,
+) -> Result<Vec<Pubkey>> {
+    let mut collected = match mule {
+        Some(mule) => MuleContext::fetch(client, &mule)?.lookup_table_addresses(),
+        None => vec![],
+    };
+
+    for address in addresses {
+        if !collected.contains(&address) {
+            collected.push(address);
+        }
+    }
+
+    Ok(collected)
+}
+
+/// Sends one lookup table transaction, or simulates it on dry runs.
+fn send_lut_tx(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    ixs: Vec<Instruction>,
+    priority: &Priority,
+    options: &TransactionOptions,
+) -> Result<()> {
+    // lookup table instructions never go through a lookup table themselves
+    if let Some(sig) = execute_ixs(client, signers, ixs, &[], priority, options)? {
+        println!("Tx: {sig}");
+    }
+
+    Ok(())
+}
+
+/// Extends `lookup_table` with `addresses`, starting with `first` in the first transaction.
+///
+/// Only the first transaction is simulated on dry runs, as the following ones
+/// depend on it having landed.
+fn extend_in_chunks(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    lookup_table: &Pubkey,
+    mut first: Vec<Instruction>,
+    addresses: &[Pubkey],
+    priority: &Priority,
+    options: &TransactionOptions,
+) -> Result<()> {
+    let authority = signers[0].pubkey();
+
+    let mut extends = addresses.chunks(EXTEND_CHUNK_SIZE).map(|chunk| {
+        extend_lookup_table(*lookup_table, authority, Some(authority), chunk.to_vec())
+    });
+
+    first.extend(extends.next());
+
+    let transactions: Vec<Vec<Instruction>> = std::iter::once(first)
+        .chain(extends.map(|ix| vec![ix]))
+        .filter(|ixs| !ixs.is_empty())
+        .collect();
+
+    let total = transactions.len();
+
+    if options.sign_only && total > 1 {
+        return Err(anyhow!(
+            "{total} transactions are needed, but --sign-only exports a single one. Pass at most {EXTEND_CHUNK_SIZE} addresses at a time"
+        ));
+    }
+    for (i, ixs) in transactions.into_iter().enumerate() {
+        options.progress(format!(
+            "Sending lookup table transaction {}/{total}",
+            i + 1
+        ));
+        send_lut_tx(client, signers, ixs, priority, options)?;
+
+        if options.dry_run && i + 1 < total {
+            println!(
+                "{} more transaction(s) not simulated as they depend on this one",
+                total - i - 1
+            );
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+pub fn handle_lut_create(args: LutCreateArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let authority_sk = config.keypair;
+
+    let authority = authority_sk.pubkey();
+
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+
+    let addresses = collect_addresses(&config.client, args.mule, args.addresses)?;
+
+    // the recent slot must still be in the slot hashes sysvar, so use a finalized one
+    let recent_slot = config
+        .client
+        .get_slot_with_commitment(CommitmentConfig::finalized())?;
+
+    let (ix, lookup_table) = create_lookup_table(authority, authority, recent_slot);
+
+    args.transaction.progress(format!(
+        "Creating lookup table {lookup_table} with {} address(es)",
+        addresses.len()
+    ));
+
+    extend_in_chunks(
+        &config.client,
+        &transaction_signers(&authority_sk, nonce_authority.as_deref()),
+        &lookup_table,
+        vec![ix],
+        &addresses,
+        &args.priority,
+        &args.transaction,
+    )?;
+
+    if !args.transaction.dry_run && !args.transaction.sign_only {
+        println!(
+            "Lookup table {lookup_table} created. Pass it as --lookup-table from the next slot on."
+        );
+    }
+
+    Ok(())
+}
+
+pub fn handle_lut_extend(args: LutExtendArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let authority_sk = config.keypair;
+
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+
+    let existing = fetch_lookup_tables(&config.client, &[args.lookup_table])?
+        .remove(0)
+        .addresses;
+
+    let addresses: Vec<Pubkey> = collect_addresses(&config.client, args.mule, args.addresses)?
+        .into_iter()
+        .filter(|x| !existing.contains(x))
+        .collect();
+
+    if addresses.is_empty() {
+        args.transaction.progress(format!(
+            "Lookup table {} already holds every address",
+            args.lookup_table
+        ));
+        return Ok(());
+    }
+
+    args.transaction.progress(format!(
+        "Adding {} address(es) to lookup table {}",
+        addresses.len(),
+        args.lookup_table
+    ));
+
+    extend_in_chunks(
+        &config.client,
+        &transaction_signers(&authority_sk, nonce_authority.as_deref()),
+        &args.lookup_table,
+        vec![],
+        &addresses,
+        &args.priority,
+        &args.transaction,
+    )
+}
+
+pub fn handle_lut_show(args: LutShowArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let data = config.client.get_account_data(&args.lookup_table)?;
+    let table = AddressLookupTable::deserialize(&data)
+        .map_err(|_| anyhow!("{} is not an address lookup table", args.lookup_table))?;
+
+    let view = LookupTableView {
+        address: args.lookup_table.to_string(),
+        authority: table.meta.authority.map(|x| x.to_string()),
+        deactivation_slot: (table.meta.deactivation_slot != u64::MAX)
+            .then_some(table.meta.deactivation_slot),
+        last_extended_slot: table.meta.last_extended_slot,
+        addresses: table.addresses.iter().map(|x| x.to_string()).collect(),
+    };
+
+    match args.output {
+        OutputFormat::Table => {
+            print_table(&[
+                ("Address", view.address.clone()),
+                (
+                    "Authority",
+                    view.authority
+                        .clone()
+                        .unwrap_or_else(|| "frozen".to_string()),
+                ),
+                (
+                    "Deactivation slot",
+                    view.deactivation_slot
+                        .map(|x| x.to_string())
+                        .unwrap_or_else(|| "active".to_string()),
+                ),
+                ("Last extended slot", view.last_extended_slot.to_string()),
+                ("Addresses", view.addresses.len().to_string()),
+            ]);
+
+            for (i, address) in view.addresses.iter().enumerate() {
+                println!("  {i:>3} {address}");
+            }
+        }
+        OutputFormat::Json => print_json(&view)?,
+    }
+
+    Ok(())
+}
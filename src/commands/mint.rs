@@ -0,0 +1,41 @@
+use super::*;
+
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::account::Account;
+
+/// Base fields of an SPL Token or Token-2022 mint.
+#[derive(Debug, Clone, Copy)]
+pub struct MintInfo {
+    pub address: Pubkey,
+    pub token_program: Pubkey,
+    pub supply: u64,
+    pub decimals: u8,
+}
+
+impl MintInfo {
+    pub fn from_account(address: &Pubkey, account: &Account) -> Result<Self> {
+        if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+            return Err(anyhow!("Account {address} is not a token mint"));
+        }
+
+        // base mint layout: mint authority (36) | supply (8) | decimals (1)
+        let data = &account.data;
+        if data.len() < 45 {
+            return Err(anyhow!("Account {address} is not a token mint"));
+        }
+
+        let mut supply = [0u8; 8];
+        supply.copy_from_slice(&data[36..44]);
+
+        Ok(Self {
+            address: *address,
+            token_program: account.owner,
+            supply: u64::from_le_bytes(supply),
+            decimals: data[44],
+        })
+    }
+
+    pub fn fetch(client: &RpcClient, address: &Pubkey) -> Result<Self> {
+        Self::from_account(address, &client.get_account(address)?)
+    }
+}
@@ -1,18 +1,56 @@
+mod batch_swap_to_fungible;
+mod broadcast;
 mod create;
+mod eligible;
+mod fund;
+mod list;
+mod lut;
+mod mint;
+mod nonce;
+mod program_accounts;
+mod show;
+mod sign;
+mod status;
+mod swap_accounts;
+mod swap_marker;
 mod swap_to_fungible;
+mod swap_to_non_fungible;
+mod update;
+mod wallet;
 
 
 // Rexport internal module types.
+pub use batch_swap_to_fungible::*;
+pub use broadcast::*;
 pub use create::*;
+pub use eligible::*;
+pub use fund::*;
+pub use list::*;
+pub use lut::*;
+pub use mint::*;
+pub use nonce::*;
+pub use program_accounts::*;
+pub use show::*;
+pub use sign::*;
+pub use status::*;
+pub use swap_accounts::*;
+pub use swap_marker::*;
 pub use swap_to_fungible::*;
+pub use swap_to_non_fungible::*;
+pub use update::*;
+pub use wallet::*;
 
 
 // Internal lib
 pub use crate::{
+    multisig::{propose_admin_ixs, MultisigOptions},
+    offline::sign_only_tx,
     setup::CliConfig,
+    simulation::simulate_tx,
     transaction::{
-        get_compute_units, get_priority_fee, send_and_confirm_tx, send_and_confirm_tx_with_spinner,
-        Priority,
+        execute_ixs, fetch_lookup_tables, get_compute_units, get_priority_fee,
+        send_and_confirm_tx_with_resend, transaction_signers, with_compute_budget, Priority,
+        TransactionOptions,
     },
 };
 
@@ -0,0 +1,116 @@
+use crate::{
+    output::{print_json, print_table, OutputFormat},
+    setup::CliSigner,
+    transaction::get_nonce_data,
+};
+
+use super::*;
+
+use solana_program::system_instruction;
+use solana_sdk::nonce::State as NonceState;
+
+pub struct NonceCreateArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    /// Keypair of the new nonce account
+    pub nonce_keypair: PathBuf,
+    /// Defaults to the fee payer
+    pub authority: Option<Pubkey>,
+    pub priority: Priority,
+    pub transaction: TransactionOptions,
+}
+
+pub struct NonceShowArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub address: Pubkey,
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct NonceView {
+    pub address: String,
+    pub authority: String,
+    /// Stored nonce, used as the blockhash of durable transactions
+    pub nonce: String,
+    pub lamports_per_signature: u64,
+}
+
+pub fn handle_nonce_create(args: NonceCreateArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let payer_sk = config.keypair;
+
+    let payer = payer_sk.pubkey();
+
+    let nonce_sk = read_keypair_file(&args.nonce_keypair).map_err(|_| {
+        anyhow!(
+            "Unable to read nonce keypair file {}",
+            args.nonce_keypair.display()
+        )
+    })?;
+    let nonce_account = nonce_sk.pubkey();
+
+    if config.client.get_account(&nonce_account).is_ok() {
+        return Err(anyhow!("Account {nonce_account} already exists"));
+    }
+
+    let authority = args.authority.unwrap_or(payer);
+    let lamports = config
+        .client
+        .get_minimum_balance_for_rent_exemption(NonceState::size())?;
+
+    let ixs =
+        system_instruction::create_nonce_account(&payer, &nonce_account, &authority, lamports);
+
+    // the nonce does not exist yet, so this transaction uses a recent blockhash
+    let options = TransactionOptions {
+        nonce_account: None,
+        nonce_authority: None,
+        lookup_tables: vec![],
+        ..args.transaction
+    };
+
+    let signers: Vec<&CliSigner> = vec![&*payer_sk, &nonce_sk];
+
+    options.progress(format!(
+        "Creating nonce account {nonce_account} with authority {authority}"
+    ));
+
+    let sig = execute_ixs(&config.client, &signers, ixs, &[], &args.priority, &options)?;
+
+    if let Some(sig) = sig {
+        println!("Nonce account {nonce_account} created. Tx: {sig}");
+        println!("Pass it as --nonce-account to sign transactions that do not expire.");
+    }
+
+    Ok(())
+}
+
+pub fn handle_nonce_show(args: NonceShowArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let data = get_nonce_data(&config.client, &args.address)?;
+
+    let view = NonceView {
+        address: args.address.to_string(),
+        authority: data.authority.to_string(),
+        nonce: data.blockhash().to_string(),
+        lamports_per_signature: data.get_lamports_per_signature(),
+    };
+
+    match args.output {
+        OutputFormat::Table => print_table(&[
+            ("Address", view.address.clone()),
+            ("Authority", view.authority.clone()),
+            ("Nonce", view.nonce.clone()),
+            (
+                "Lamports per signature",
+                view.lamports_per_signature.to_string(),
+            ),
+        ]),
+        OutputFormat::Json => print_json(&view)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,57 @@
+use super::*;
+
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+};
+use solana_program::hash::hash;
+use solana_sdk::account::Account;
+
+/// Offset of the authority in mules: discriminator (8) | seed (32) | authority (32).
+pub const MULE_AUTHORITY_OFFSET: usize = 8 + 32;
+/// Offset of the fungible asset in mules, right after the authority.
+pub const MULE_FUNGIBLE_ASSET_OFFSET: usize = MULE_AUTHORITY_OFFSET + 32;
+/// Offset of the mule in swap markers: discriminator (8) | mule (32).
+pub const SWAP_MARKER_MULE_OFFSET: usize = 8;
+
+/// Anchor account discriminator: the first 8 bytes of `sha256("account:<name>")`.
+pub fn account_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("account:{name}").as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Memcmp filter matching accounts holding `key` at `offset`.
+pub fn pubkey_filter(offset: usize, key: &Pubkey) -> RpcFilterType {
+    RpcFilterType::Memcmp(Memcmp::new_raw_bytes(offset, key.to_bytes().to_vec()))
+}
+
+/// Fetches every mule program account of the given anchor account type, narrowed
+/// down by any additional memcmp `filters`.
+pub fn get_mule_program_accounts(
+    client: &RpcClient,
+    account_name: &str,
+    filters: Vec<RpcFilterType>,
+) -> Result<Vec<(Pubkey, Account)>> {
+    let mut all_filters = vec![RpcFilterType::Memcmp(Memcmp::new_raw_bytes(
+        0,
+        account_discriminator(account_name).to_vec(),
+    ))];
+    all_filters.extend(filters);
+
+    let accounts = client.get_program_accounts_with_config(
+        &libreplex_mule_client::ID,
+        RpcProgramAccountsConfig {
+            filters: Some(all_filters),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+
+    Ok(accounts)
+}
@@ -0,0 +1,135 @@
+use crate::{
+    filter::Filter,
+    output::{print_json, print_table, OutputFormat},
+};
+
+use super::*;
+
+pub struct ShowArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    pub output: OutputFormat,
+}
+
+/// Flattened, printable view of an on-chain `Mule` account.
+#[derive(Debug, Serialize)]
+pub struct MuleView {
+    pub address: String,
+    pub name: String,
+    pub seed: String,
+    pub authority: String,
+    pub update_auth: Option<String>,
+    pub fungible_asset: String,
+    pub filter: String,
+    pub base_swap_rate: u64,
+    pub auto_generate_swap_marker: bool,
+    pub fee_per_swap_lamports: Option<u64>,
+    pub fee_rate_per_swap_basis_points: Option<u16>,
+    pub fee_per_swap_spl_amount: Option<u64>,
+    pub burn_spl_basis_points: Option<u16>,
+    pub swap_fee_treasury: Option<String>,
+}
+
+impl MuleView {
+    pub fn new(address: &Pubkey, mule: &Mule) -> Self {
+        Self {
+            address: address.to_string(),
+            name: mule.name.clone(),
+            seed: mule.seed.to_string(),
+            authority: mule.authority.to_string(),
+            update_auth: mule.update_auth.map(|x| x.to_string()),
+            fungible_asset: mule.fungible_asset.to_string(),
+            filter: Filter::from(mule.filter.clone()).to_string(),
+            base_swap_rate: mule.base_swap_rate,
+            auto_generate_swap_marker: mule.auto_generate_swap_marker,
+            fee_per_swap_lamports: mule.fee_per_swap_lamports,
+            fee_rate_per_swap_basis_points: mule.fee_rate_per_swap_basis_points,
+            fee_per_swap_spl_amount: mule.fee_per_swap_spl_amount,
+            burn_spl_basis_points: mule.burn_spl_basis_points,
+            swap_fee_treasury: mule.swap_fee_treasury.map(|x| x.to_string()),
+        }
+    }
+
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        fn or_none<T: ToString>(value: &Option<T>) -> String {
+            value
+                .as_ref()
+                .map(|x| x.to_string())
+                .unwrap_or_else(|| "-".to_string())
+        }
+
+        vec![
+            ("Address", self.address.clone()),
+            ("Name", self.name.clone()),
+            ("Seed", self.seed.clone()),
+            ("Authority", self.authority.clone()),
+            ("Update auth", or_none(&self.update_auth)),
+            ("Fungible asset", self.fungible_asset.clone()),
+            ("Filter", self.filter.clone()),
+            ("Base swap rate", self.base_swap_rate.to_string()),
+            (
+                "Auto-generate swap marker",
+                self.auto_generate_swap_marker.to_string(),
+            ),
+            ("Fee per swap (lamports)", or_none(&self.fee_per_swap_lamports)),
+            (
+                "Fee rate per swap (bps)",
+                or_none(&self.fee_rate_per_swap_basis_points),
+            ),
+            ("Fee per swap (SPL)", or_none(&self.fee_per_swap_spl_amount)),
+            ("Burn SPL (bps)", or_none(&self.burn_spl_basis_points)),
+            ("Swap fee treasury", or_none(&self.swap_fee_treasury)),
+        ]
+    }
+}
+
+/// A mule with the swap counts derived from its swap markers.
+///
+/// The mule account keeps no swap counters, so every asset swapped in with a
+/// swap marker is counted instead.
+#[derive(Debug, Serialize)]
+pub struct MuleDetails {
+    #[serde(flatten)]
+    pub mule: MuleView,
+    pub swap_markers: usize,
+}
+
+pub fn handle_show(args: ShowArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let account = config.client.get_account(&args.mule)?;
+
+    if account.owner != libreplex_mule_client::ID {
+        return Err(anyhow!(
+            "Account {} is not owned by the mule program",
+            args.mule
+        ));
+    }
+
+    let mule_obj = Mule::from_bytes(&account.data)
+        .map_err(|e| anyhow!("Unable to decode mule {}: {e}", args.mule))?;
+
+    let swap_markers = get_mule_program_accounts(
+        &config.client,
+        "SwapMarker",
+        vec![pubkey_filter(SWAP_MARKER_MULE_OFFSET, &args.mule)],
+    )?
+    .len();
+
+    let details = MuleDetails {
+        mule: MuleView::new(&args.mule, &mule_obj),
+        swap_markers,
+    };
+
+    match args.output {
+        OutputFormat::Table => {
+            let mut rows = details.mule.rows();
+            rows.push(("Swap markers", details.swap_markers.to_string()));
+            print_table(&rows);
+        }
+        OutputFormat::Json => print_json(&details)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,89 @@
+use crate::offline::{required_signers, TransactionEnvelope};
+
+use super::*;
+
+use solana_sdk::signature::Signature;
+
+use std::str::FromStr;
+
+pub struct SignArgs {
+    pub keypair_path: Option<PathBuf>,
+    /// Envelope exported with --sign-only
+    pub file: PathBuf,
+    /// Signatures collected elsewhere, as PUBKEY=SIGNATURE
+    pub signers: Vec<String>,
+    /// Writes the signed envelope here instead of stdout
+    pub output: Option<PathBuf>,
+}
+
+/// Parses a `PUBKEY=SIGNATURE` pair, as printed by the `solana` CLI with `--sign-only`.
+fn parse_presigner(value: &str) -> Result<(Pubkey, Signature)> {
+    let (pubkey, signature) = value
+        .split_once('=')
+        .ok_or_else(|| anyhow!("Invalid signer '{value}'. Expected PUBKEY=SIGNATURE"))?;
+
+    let pubkey = Pubkey::from_str(pubkey).map_err(|_| anyhow!("Invalid pubkey '{pubkey}'"))?;
+    let signature =
+        Signature::from_str(signature).map_err(|_| anyhow!("Invalid signature '{signature}'"))?;
+
+    Ok((pubkey, signature))
+}
+
+pub fn handle_sign(args: SignArgs) -> Result<()> {
+    let envelope = TransactionEnvelope::read(&args.file)?;
+    let mut tx = envelope.transaction()?;
+
+    let message_data = tx.message.serialize();
+    let required = required_signers(&tx.message).to_vec();
+
+    let position = |pubkey: &Pubkey| {
+        required
+            .iter()
+            .position(|x| x == pubkey)
+            .ok_or_else(|| anyhow!("{pubkey} is not a signer of this transaction"))
+    };
+
+    for value in &args.signers {
+        let (pubkey, signature) = parse_presigner(value)?;
+
+        if !signature.verify(pubkey.as_ref(), &message_data) {
+            return Err(anyhow!(
+                "Signature {signature} of {pubkey} does not match this transaction"
+            ));
+        }
+
+        tx.signatures[position(&pubkey)?] = signature;
+    }
+
+    // the keypair is optional when only collected signatures are added
+    if args.signers.is_empty() || args.keypair_path.is_some() {
+        // signing needs no RPC, so this works on an air-gapped machine
+        let signer = CliConfig::builder(args.keypair_path, None).build_signer()?;
+        let pubkey = signer.pubkey();
+
+        let signature = signer.try_sign_message(&message_data)?;
+
+        // a bare public key signs with an empty signature
+        if signature == Signature::default() {
+            return Err(anyhow!(
+                "{pubkey} is a public key. Pass its keypair file to sign"
+            ));
+        }
+
+        tx.signatures[position(&pubkey)?] = signature;
+    }
+
+    let signed = TransactionEnvelope::new(&tx, envelope.encoding, envelope.nonce_account()?)?;
+
+    signed.print_summary();
+
+    match args.output {
+        Some(path) => {
+            signed.write(&path)?;
+            eprintln!("Signed transaction written to {}", path.display());
+        }
+        None => println!("{}", serde_json::to_string_pretty(&signed)?),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,144 @@
+use crate::{
+    amount::base_units_to_ui_amount,
+    output::{print_json, print_table, OutputFormat},
+    simulation::token_account_amount,
+};
+
+use super::*;
+
+use spl_token::state::AccountState;
+
+/// Offset of the `state` field in SPL Token and Token-2022 token accounts.
+const TOKEN_ACCOUNT_STATE_OFFSET: usize = 108;
+
+pub struct StatusArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MuleStatus {
+    pub mule: String,
+    pub fungible_asset: String,
+    pub liquidity_token_account: String,
+    /// Liquidity in base units
+    pub liquidity: u64,
+    pub liquidity_ui: String,
+    pub escrowed_assets: usize,
+    pub quote: SwapQuote,
+    /// Number of swaps to fungible the current liquidity can honour
+    pub swaps_remaining: Option<u64>,
+    pub issues: Vec<String>,
+}
+
+pub fn handle_status(args: StatusArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let mule = MuleContext::fetch(&config.client, &args.mule)?;
+    let mint = MintInfo::fetch(&config.client, &mule.mule.fungible_asset)?;
+    let quote = mule.quote_swap_to_fungible();
+
+    let mut issues = vec![];
+
+    let liquidity_token_account = mule.fungible_token_account(&args.mule);
+
+    let liquidity = match config.client.get_account(&liquidity_token_account) {
+        Ok(account) => {
+            if account.data.get(TOKEN_ACCOUNT_STATE_OFFSET) == Some(&(AccountState::Frozen as u8))
+            {
+                issues.push(format!(
+                    "Liquidity token account {liquidity_token_account} is frozen"
+                ));
+            }
+            token_account_amount(&account).unwrap_or(0)
+        }
+        Err(_) => {
+            issues.push(format!(
+                "Liquidity token account {liquidity_token_account} does not exist. Fund the mule first"
+            ));
+            0
+        }
+    };
+
+    if liquidity == 0 {
+        issues.push("Mule has no fungible liquidity".to_string());
+    }
+
+    if quote.gross == 0 {
+        issues.push("Base swap rate is zero".to_string());
+    }
+
+    let charges_fees = quote.fee > 0 || quote.fee_lamports > 0;
+    match mule.mule.swap_fee_treasury {
+        Some(treasury) => {
+            if config.client.get_account(&treasury).is_err() {
+                issues.push(format!("Swap fee treasury {treasury} does not exist"));
+            }
+        }
+        None if charges_fees => {
+            issues.push("Mule charges swap fees but has no swap fee treasury".to_string());
+        }
+        None => {}
+    }
+
+    if quote.fee.saturating_add(quote.burn) > quote.gross {
+        issues.push("Swap fees and burn exceed the base swap rate".to_string());
+    }
+
+    let escrowed_assets = get_wallet_non_fungibles(&config.client, &args.mule)?.len();
+
+    let status = MuleStatus {
+        mule: args.mule.to_string(),
+        fungible_asset: mint.address.to_string(),
+        liquidity_token_account: liquidity_token_account.to_string(),
+        liquidity,
+        liquidity_ui: base_units_to_ui_amount(liquidity, mint.decimals),
+        escrowed_assets,
+        quote,
+        swaps_remaining: liquidity.checked_div(quote.gross),
+        issues,
+    };
+
+    match args.output {
+        OutputFormat::Table => {
+            print_table(&[
+                ("Mule", status.mule.clone()),
+                ("Fungible asset", status.fungible_asset.clone()),
+                ("Liquidity account", status.liquidity_token_account.clone()),
+                ("Liquidity", status.liquidity_ui.clone()),
+                ("Escrowed assets", status.escrowed_assets.to_string()),
+                (
+                    "Per swap",
+                    format!(
+                        "{} released, {} fee, {} burnt, {} to swapper",
+                        base_units_to_ui_amount(quote.gross, mint.decimals),
+                        base_units_to_ui_amount(quote.fee, mint.decimals),
+                        base_units_to_ui_amount(quote.burn, mint.decimals),
+                        base_units_to_ui_amount(quote.net, mint.decimals),
+                    ),
+                ),
+                (
+                    "Swaps remaining",
+                    status
+                        .swaps_remaining
+                        .map(|x| x.to_string())
+                        .unwrap_or_else(|| "-".to_string()),
+                ),
+            ]);
+
+            if status.issues.is_empty() {
+                println!("No issues found");
+            } else {
+                println!("Issues:");
+                for issue in &status.issues {
+                    println!("  - {issue}");
+                }
+            }
+        }
+        OutputFormat::Json => print_json(&status)?,
+    }
+
+    Ok(())
+}
@@ -0,0 +1,438 @@
+use super::*;
+
+use libreplex_nico::{AccountData, Nico};
+use mpl_token_metadata::{
+    accounts::Metadata,
+    types::{Key, ProgrammableConfig, TokenStandard},
+};
+use solana_client::rpc_client::RpcClient;
+use solana_program::pubkey;
+use solana_sdk::{account::ReadableAccount, account_info::AccountInfo, instruction::AccountMeta};
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+pub const AUTH_RULES_PROGRAM_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
+pub const MPL_CORE_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
+pub const NIFTY_ASSET_ID: Pubkey = pubkey!("AssetGtQBTSgm5s91d1RAQod5JmaZiJDxqsgtqrZud73");
+pub const MPL_TOKEN_METADATA_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+pub const SYSVAR_INSTRUCTIONS_PROGRAM_ID: Pubkey =
+    pubkey!("Sysvar1nstructions1111111111111111111111111");
+
+/// Offset of the owner in Core assets: key (1) | owner (32).
+pub const CORE_OWNER_OFFSET: usize = 1;
+/// Offset of the owner in Nifty assets: discriminator, state, standard, mutable (4) | owner (32).
+pub const NIFTY_OWNER_OFFSET: usize = 4;
+
+/// Amounts of a single swap to fungible, in base units of the fungible mint.
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SwapQuote {
+    /// Base swap rate of the mule, released from its liquidity on every swap
+    pub gross: u64,
+    /// SPL fees taken from the swapper
+    pub fee: u64,
+    /// Amount burnt on swap
+    pub burn: u64,
+    /// Amount received by the swapper
+    pub net: u64,
+    /// SOL fee paid by the swapper
+    pub fee_lamports: u64,
+}
+
+/// A decoded mule together with the token program owning its fungible mint.
+pub struct MuleContext {
+    pub address: Pubkey,
+    pub mule: Mule,
+    pub fungible_token_program: Pubkey,
+}
+
+impl MuleContext {
+    pub fn fetch(client: &RpcClient, address: &Pubkey) -> Result<Self> {
+        let data = client.get_account_data(address)?;
+        let mule = Mule::from_bytes(&data)?;
+        let fungible_token_program = client.get_account(&mule.fungible_asset)?.owner;
+
+        Ok(Self {
+            address: *address,
+            mule,
+            fungible_token_program,
+        })
+    }
+
+    /// Fungible amounts, in base units, involved in a single swap to fungible.
+    pub fn quote_swap_to_fungible(&self) -> SwapQuote {
+        let gross = self.mule.base_swap_rate;
+        let rate_fee = self
+            .mule
+            .fee_rate_per_swap_basis_points
+            .map(|bps| (gross as u128 * bps as u128 / 10_000) as u64)
+            .unwrap_or(0);
+        let fee = rate_fee.saturating_add(self.mule.fee_per_swap_spl_amount.unwrap_or(0));
+        let burn = self
+            .mule
+            .burn_spl_basis_points
+            .map(|bps| (gross as u128 * bps as u128 / 10_000) as u64)
+            .unwrap_or(0);
+
+        SwapQuote {
+            gross,
+            fee,
+            burn,
+            net: gross.saturating_sub(fee).saturating_sub(burn),
+            fee_lamports: self.mule.fee_per_swap_lamports.unwrap_or(0),
+        }
+    }
+
+    /// Accounts used by every swap under this mule, suitable for an address lookup table.
+    pub fn lookup_table_addresses(&self) -> Vec<Pubkey> {
+        let mut addresses = vec![
+            // static programs and sysvars passed as remaining accounts
+            spl_token::ID,
+            spl_token_2022::ID,
+            system_program::ID,
+            MPL_CORE_ID,
+            NIFTY_ASSET_ID,
+            MPL_TOKEN_METADATA_ID,
+            spl_associated_token_account::ID,
+            AUTH_RULES_PROGRAM_ID,
+            SYSVAR_INSTRUCTIONS_PROGRAM_ID,
+            libreplex_mule_client::ID,
+            // fixed accounts of the mule
+            self.address,
+            self.mule.fungible_asset,
+            self.fungible_token_account(&self.address),
+        ];
+
+        if let Some(treasury) = self.mule.swap_fee_treasury {
+            addresses.push(treasury);
+        }
+
+        addresses.sort();
+        addresses.dedup();
+
+        addresses
+    }
+
+    /// Associated token account of `owner` for the mule's fungible mint.
+    pub fn fungible_token_account(&self, owner: &Pubkey) -> Pubkey {
+        get_associated_token_address_with_program_id(
+            owner,
+            &self.mule.fungible_asset,
+            &self.fungible_token_program,
+        )
+    }
+}
+
+pub fn get_swap_marker(mule: &Pubkey, non_fungible_asset: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"swap_marker", mule.as_ref(), non_fungible_asset.as_ref()],
+        &libreplex_mule_client::ID,
+    )
+    .0
+}
+
+pub fn get_token_record(mint: &Pubkey, token_account: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint.as_ref(),
+            b"token_record",
+            token_account.as_ref(),
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+    .0
+}
+
+pub fn get_metadata_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[b"metadata", MPL_TOKEN_METADATA_ID.as_ref(), mint.as_ref()],
+        &MPL_TOKEN_METADATA_ID,
+    )
+    .0
+}
+
+pub fn get_edition_address(mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            b"metadata",
+            MPL_TOKEN_METADATA_ID.as_ref(),
+            mint.as_ref(),
+            b"edition",
+        ],
+        &MPL_TOKEN_METADATA_ID,
+    )
+    .0
+}
+
+/// Returns the token standard of a Token Metadata mint.
+///
+/// Legacy metadata created before token standards existed has none, in which
+/// case it is inferred from the mint supply and decimals and the edition account.
+pub fn classify_token_standard(
+    client: &RpcClient,
+    mint: &Pubkey,
+    metadata: &Metadata,
+) -> Result<TokenStandard> {
+    if let Some(x) = &metadata.token_standard {
+        return Ok(x.clone());
+    }
+
+    let accounts = client.get_multiple_accounts(&[*mint, get_edition_address(mint)])?;
+
+    let mint_info = match &accounts[0] {
+        Some(x) => MintInfo::from_account(mint, x)?,
+        None => return Err(anyhow!("Mint {mint} does not exist")),
+    };
+
+    if mint_info.decimals > 0 {
+        return Ok(TokenStandard::Fungible);
+    }
+
+    let edition_key = accounts[1]
+        .as_ref()
+        .filter(|x| x.owner == MPL_TOKEN_METADATA_ID)
+        .and_then(|x| x.data.first().copied());
+
+    Ok(match edition_key {
+        Some(x) if x == Key::MasterEditionV1 as u8 || x == Key::MasterEditionV2 as u8 => {
+            TokenStandard::NonFungible
+        }
+        Some(x) if x == Key::EditionV1 as u8 => TokenStandard::NonFungibleEdition,
+        _ if mint_info.supply <= 1 => TokenStandard::NonFungible,
+        _ => TokenStandard::FungibleAsset,
+    })
+}
+
+/// On-chain details of a non-fungible asset needed to swap it and to check it
+/// against a mule filter.
+pub struct ResolvedNonFungible {
+    /// Program owning the asset account
+    pub owner: Pubkey,
+    /// Token Metadata account, for mints only
+    pub metadata: Option<Metadata>,
+    /// Group (collection) of the asset as resolved by nico
+    pub group: Option<Pubkey>,
+}
+
+impl ResolvedNonFungible {
+    /// Token program of the asset's token accounts. SPL Token unless the asset is a Token-2022 mint.
+    pub fn token_program(&self) -> Pubkey {
+        if self.owner == spl_token_2022::ID {
+            spl_token_2022::ID
+        } else {
+            spl_token::ID
+        }
+    }
+
+    /// Verified Token Metadata creators of the asset.
+    pub fn verified_creators(&self) -> Vec<Pubkey> {
+        self.metadata
+            .as_ref()
+            .and_then(|x| x.creators.as_ref())
+            .map(|creators| {
+                creators
+                    .iter()
+                    .filter(|x| x.verified)
+                    .map(|x| x.address)
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Fetches a non-fungible asset (and its Token Metadata if it is a mint) and
+/// resolves its group through nico.
+pub fn resolve_non_fungible(
+    client: &RpcClient,
+    non_fungible_asset: &Pubkey,
+) -> Result<ResolvedNonFungible> {
+    let mut account_non_fungible = client.get_account(non_fungible_asset)?;
+    let data_non_fungible = AccountInfo::new(
+        non_fungible_asset,
+        false,
+        false,
+        &mut account_non_fungible.lamports,
+        &mut account_non_fungible.data,
+        &account_non_fungible.owner,
+        account_non_fungible.executable,
+        account_non_fungible.rent_epoch,
+    );
+
+    // if it is a mint, then grab some metadata as well
+    let metadata = get_metadata_address(non_fungible_asset);
+
+    let account_metadata = client.get_account(&metadata);
+    let mut metadata_data: Option<AccountInfo> = None;
+    let mut metadata_obj: Option<Metadata> = None;
+
+    let mut lamports = 0;
+    let mut data: Vec<u8> = vec![];
+    if let Ok(mut md) = account_metadata {
+        if md.owner == MPL_TOKEN_METADATA_ID {
+            metadata_obj = Some(Metadata::from_bytes(md.data())?);
+            data.append(&mut md.data);
+            metadata_data = Some(AccountInfo::new(
+                &metadata,
+                false,
+                false,
+                &mut lamports,
+                &mut data,
+                &MPL_TOKEN_METADATA_ID,
+                md.executable,
+                md.rent_epoch,
+            ));
+        }
+    }
+    let nico: Nico = Nico::from_raw_data(&data_non_fungible, metadata_data.as_ref(), None, None);
+
+    Ok(ResolvedNonFungible {
+        owner: account_non_fungible.owner,
+        metadata: metadata_obj,
+        group: nico.group,
+    })
+}
+
+/// Resolves the remaining accounts the mule program needs to move a non-fungible
+/// asset (Core, Nifty, Token Metadata NFT, pNFT or Token-2022 NFT) from
+/// `source_token_account` into the associated token account of `target_owner`.
+///
+/// `source_token_account` is only set when the asset is a mint.
+pub fn get_swap_remaining_accounts(
+    client: &RpcClient,
+    non_fungible_asset: &Pubkey,
+    asset_group: Option<Pubkey>,
+    source_token_account: Option<Pubkey>,
+    target_owner: &Pubkey,
+) -> Result<Vec<AccountMeta>> {
+    let mut remaining_accounts = vec![
+        AccountMeta {
+            pubkey: spl_token::ID,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: system_program::ID,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: MPL_CORE_ID,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: MPL_TOKEN_METADATA_ID,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: spl_associated_token_account::ID,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: AUTH_RULES_PROGRAM_ID,
+            is_signer: false,
+            is_writable: false,
+        },
+        AccountMeta {
+            pubkey: SYSVAR_INSTRUCTIONS_PROGRAM_ID,
+            is_signer: false,
+            is_writable: false,
+        },
+    ];
+
+    let resolved = resolve_non_fungible(client, non_fungible_asset)?;
+    let token_program = resolved.token_program();
+
+    if token_program == spl_token_2022::ID {
+        remaining_accounts.push(AccountMeta {
+            pubkey: spl_token_2022::ID,
+            is_signer: false,
+            is_writable: false,
+        });
+    }
+
+    if let Some(x) = asset_group {
+        remaining_accounts.push(AccountMeta {
+            pubkey: x,
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    let metadata = get_metadata_address(non_fungible_asset);
+    if let Some(x) = source_token_account {
+        // add metadata
+        remaining_accounts.push(AccountMeta {
+            pubkey: metadata,
+            is_signer: false,
+            is_writable: true,
+        });
+
+        // add source token record
+        remaining_accounts.push(AccountMeta {
+            pubkey: get_token_record(non_fungible_asset, &x),
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    if let Some(metadata_obj) = &resolved.metadata {
+        match classify_token_standard(client, non_fungible_asset, metadata_obj)? {
+            TokenStandard::ProgrammableNonFungible
+            | TokenStandard::ProgrammableNonFungibleEdition => {
+                if let Some(ProgrammableConfig::V1 { rule_set: Some(x) }) =
+                    &metadata_obj.programmable_config
+                {
+                    remaining_accounts.push(AccountMeta {
+                        pubkey: *x,
+                        is_signer: false,
+                        is_writable: false,
+                    });
+                }
+            }
+            TokenStandard::NonFungible | TokenStandard::NonFungibleEdition => {}
+            x @ (TokenStandard::Fungible | TokenStandard::FungibleAsset) => {
+                return Err(anyhow!(
+                    "Asset {non_fungible_asset} has token standard {x:?} and cannot be swapped as a non-fungible"
+                ));
+            }
+        }
+        remaining_accounts.push(AccountMeta {
+            pubkey: metadata,
+            is_signer: false,
+            is_writable: true,
+        });
+    }
+
+    let target_ata =
+        get_associated_token_address_with_program_id(target_owner, non_fungible_asset, &token_program);
+    remaining_accounts.push(AccountMeta {
+        pubkey: target_ata,
+        is_signer: false,
+        is_writable: true,
+    });
+
+    remaining_accounts.push(AccountMeta {
+        pubkey: get_token_record(non_fungible_asset, &target_ata),
+        is_signer: false,
+        is_writable: true,
+    });
+
+    remaining_accounts.push(AccountMeta {
+        pubkey: get_edition_address(non_fungible_asset),
+        is_signer: false,
+        is_writable: false,
+    });
+
+    if let Some(x) = resolved.group {
+        remaining_accounts.push(AccountMeta {
+            pubkey: x,
+            is_signer: false,
+            is_writable: false,
+        });
+    }
+
+    Ok(remaining_accounts)
+}
@@ -0,0 +1,233 @@
+use crate::{
+    output::{print_json, print_table, OutputFormat},
+    simulation::token_account_amount,
+};
+
+use super::*;
+
+use solana_client::rpc_client::RpcClient;
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+pub struct SwapMarkerShowArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    pub asset: Pubkey,
+    pub output: OutputFormat,
+}
+
+pub struct SwapMarkerListArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    pub output: OutputFormat,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwapMarkerView {
+    pub address: String,
+    pub mule: String,
+    pub asset: String,
+    pub exists: bool,
+    pub escrowed: bool,
+    /// Why the escrow could not be checked, if it could not
+    pub error: Option<String>,
+}
+
+impl SwapMarkerView {
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        vec![
+            ("Swap marker", self.address.clone()),
+            ("Mule", self.mule.clone()),
+            ("Asset", self.asset.clone()),
+            ("Marker exists", self.exists.to_string()),
+            (
+                "Escrowed in mule",
+                match &self.error {
+                    Some(e) => format!("unknown: {e}"),
+                    None => self.escrowed.to_string(),
+                },
+            ),
+        ]
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SwapMarkerList {
+    pub mule: String,
+    pub total: usize,
+    pub escrowed: usize,
+    pub markers: Vec<SwapMarkerView>,
+}
+
+/// Whether an asset is held by a mule.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum EscrowStatus {
+    Escrowed,
+    NotEscrowed,
+    /// The asset belongs to a program the mule does not support
+    UnsupportedOwner(Pubkey),
+}
+
+impl EscrowStatus {
+    /// The escrow flag of a [`SwapMarkerView`] and, when it cannot be told, why.
+    fn view_fields(self) -> (bool, Option<String>) {
+        match self {
+            Self::Escrowed => (true, None),
+            Self::NotEscrowed => (false, None),
+            Self::UnsupportedOwner(owner) => (
+                false,
+                Some(format!("asset is owned by unsupported program {owner}")),
+            ),
+        }
+    }
+}
+
+/// Checks whether `asset` is currently held by `mule`.
+///
+/// Mints are escrowed in the mule's associated token account, Core and Nifty
+/// assets are owned by the mule directly. RPC failures are errors, so that they
+/// are not mistaken for a missing account.
+pub fn get_escrow_status(
+    client: &RpcClient,
+    mule: &Pubkey,
+    asset: &Pubkey,
+) -> Result<EscrowStatus> {
+    let Some(account) = client
+        .get_account_with_commitment(asset, client.commitment())?
+        .value
+    else {
+        // burnt or closed assets cannot be escrowed
+        return Ok(EscrowStatus::NotEscrowed);
+    };
+
+    let escrowed = if account.owner == spl_token::ID || account.owner == spl_token_2022::ID {
+        let escrow = get_associated_token_address_with_program_id(mule, asset, &account.owner);
+        let amount = client
+            .get_account_with_commitment(&escrow, client.commitment())?
+            .value
+            .and_then(|x| token_account_amount(&x));
+
+        matches!(amount, Some(x) if x > 0)
+    } else {
+        let owner_offset = if account.owner == MPL_CORE_ID {
+            CORE_OWNER_OFFSET
+        } else if account.owner == NIFTY_ASSET_ID {
+            NIFTY_OWNER_OFFSET
+        } else {
+            return Ok(EscrowStatus::UnsupportedOwner(account.owner));
+        };
+
+        account
+            .data
+            .get(owner_offset..owner_offset + 32)
+            .map(|owner| owner == mule.as_ref())
+            .unwrap_or(false)
+    };
+
+    Ok(match escrowed {
+        true => EscrowStatus::Escrowed,
+        false => EscrowStatus::NotEscrowed,
+    })
+}
+
+pub fn handle_swap_marker_show(args: SwapMarkerShowArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let swap_marker = get_swap_marker(&args.mule, &args.asset);
+
+    let exists = match config
+        .client
+        .get_account_with_commitment(&swap_marker, config.client.commitment())?
+        .value
+    {
+        Some(account) => {
+            SwapMarker::from_bytes(&account.data)
+                .map_err(|e| anyhow!("Unable to decode swap marker {swap_marker}: {e}"))?;
+            true
+        }
+        None => false,
+    };
+
+    let (escrowed, error) =
+        get_escrow_status(&config.client, &args.mule, &args.asset)?.view_fields();
+
+    let view = SwapMarkerView {
+        address: swap_marker.to_string(),
+        mule: args.mule.to_string(),
+        asset: args.asset.to_string(),
+        exists,
+        escrowed,
+        error,
+    };
+
+    match args.output {
+        OutputFormat::Table => print_table(&view.rows()),
+        OutputFormat::Json => print_json(&view)?,
+    }
+
+    Ok(())
+}
+
+pub fn handle_swap_marker_list(args: SwapMarkerListArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let accounts = get_mule_program_accounts(
+        &config.client,
+        "SwapMarker",
+        vec![pubkey_filter(SWAP_MARKER_MULE_OFFSET, &args.mule)],
+    )?;
+
+    let mut markers = vec![];
+
+    for (address, account) in accounts {
+        let marker = match SwapMarker::from_bytes(&account.data) {
+            Ok(x) => x,
+            Err(e) => {
+                eprintln!("Skipping {address}: unable to decode swap marker: {e}");
+                continue;
+            }
+        };
+
+        // an unsupported asset is reported on its marker, not for the whole list
+        let (escrowed, error) =
+            get_escrow_status(&config.client, &args.mule, &marker.non_fungible_asset)?
+                .view_fields();
+
+        markers.push(SwapMarkerView {
+            address: address.to_string(),
+            mule: marker.mule.to_string(),
+            asset: marker.non_fungible_asset.to_string(),
+            exists: true,
+            escrowed,
+            error,
+        });
+    }
+
+    let list = SwapMarkerList {
+        mule: args.mule.to_string(),
+        total: markers.len(),
+        escrowed: markers.iter().filter(|x| x.escrowed).count(),
+        markers,
+    };
+
+    match args.output {
+        OutputFormat::Table => {
+            for marker in &list.markers {
+                let escrowed = match (&marker.error, marker.escrowed) {
+                    (Some(e), _) => e.as_str(),
+                    (None, true) => "escrowed",
+                    (None, false) => "-",
+                };
+                println!("{}  {}  {escrowed}", marker.address, marker.asset);
+            }
+            println!(
+                "Found {} swap marker(s), {} asset(s) escrowed in mule {}",
+                list.total, list.escrowed, list.mule
+            );
+        }
+        OutputFormat::Json => print_json(&list)?,
+    }
+
+    Ok(())
+}
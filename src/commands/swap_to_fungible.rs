@@ -1,25 +1,7 @@
-use std::str::FromStr;
-
-use crate::transaction::send_and_confirm_tx_with_spinner_with_config;
-
 use super::*;
 
-use libreplex_nico::{AccountData, Nico};
-use mpl_token_metadata::{accounts::Metadata, types::TokenStandard};
-use solana_client::rpc_config::RpcSendTransactionConfig;
-use solana_program::pubkey;
-use solana_sdk::{
-    account::{self, ReadableAccount},
-    account_info::AccountInfo,
-    instruction::AccountMeta,
-};
-use spl_associated_token_account::get_associated_token_address_with_program_id;
-
-pub const AUTH_RULES_PROGRAM_ID: Pubkey = pubkey!("auth9SigNpDKz4sJJ1DfCTuZrZNSAgh9sFD3rboVmgg");
-pub const MPL_CORE_ID: Pubkey = pubkey!("CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d");
-pub const MPL_TOKEN_METADATA_ID: Pubkey = pubkey!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
-pub const SYSVAR_INSTRUCTIONS_PROGRAM_ID: Pubkey =
-    pubkey!("Sysvar1nstructions1111111111111111111111111");
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
 
 pub struct SwapToFungibleArgs {
     pub keypair_path: Option<PathBuf>,
@@ -30,6 +12,67 @@ pub struct SwapToFungibleArgs {
     // required unless asset is nifty or a core
     pub non_fungible_source_token_account: Option<Pubkey>,
     pub priority: Priority,
+    pub transaction: TransactionOptions,
+}
+
+/// Builds the swap instruction moving `non_fungible_asset` from `swapper` into the mule.
+///
+/// The asset standard is detected from its account. `asset_group` defaults to the
+/// collection of Core assets and `non_fungible_source_token_account` to the
+/// swapper's largest holding of the mint; both override the detected values when set.
+pub fn get_swap_to_fungible_ix(
+    client: &RpcClient,
+    swapper: &Pubkey,
+    mule: &MuleContext,
+    non_fungible_asset: &Pubkey,
+    asset_group: Option<Pubkey>,
+    non_fungible_source_token_account: Option<Pubkey>,
+) -> Result<Instruction> {
+    let account_non_fungible = client.get_account(non_fungible_asset)?;
+
+    let (asset_group, non_fungible_source_token_account) =
+        match detect_asset_kind(non_fungible_asset, &account_non_fungible)? {
+            AssetKind::SplToken | AssetKind::Token2022 => (
+                asset_group,
+                match non_fungible_source_token_account {
+                    Some(x) => Some(x),
+                    None => Some(find_largest_token_account(
+                        client,
+                        swapper,
+                        non_fungible_asset,
+                    )?),
+                },
+            ),
+            AssetKind::Core => (
+                asset_group.or_else(|| get_core_collection(&account_non_fungible)),
+                None,
+            ),
+            AssetKind::Nifty => (asset_group, None),
+        };
+
+    let remaining_accounts = get_swap_remaining_accounts(
+        client,
+        non_fungible_asset,
+        asset_group,
+        non_fungible_source_token_account,
+        &mule.address,
+    )?;
+
+    Ok(SwapToFungible {
+        payer: *swapper,
+        swapper: *swapper,
+        mule: mule.address,
+        cosigner: None,
+        swap_marker: get_swap_marker(&mule.address, non_fungible_asset),
+        non_fungible_asset: *non_fungible_asset,
+        fungible_asset: mule.mule.fungible_asset,
+        fungible_source_token_account: mule.fungible_token_account(&mule.address),
+        fungible_target_token_account: mule.fungible_token_account(swapper),
+        non_fungible_source_token_account,
+        system_program: system_program::ID,
+        associated_token_program: spl_associated_token_account::ID,
+    }
+    .instruction_with_remaining_accounts(remaining_accounts.as_slice()))
 }
 
 pub fn handle_swap_to_fungible(args: SwapToFungibleArgs) -> Result<()> {
@@ -39,280 +82,34 @@ pub fn handle_swap_to_fungible(args: SwapToFungibleArgs) -> Result<()> {
 
     let authority = authority_sk.pubkey();
 
-    let data = config.client.get_account_data(&args.mule)?;
-
-    let mule_obj = Mule::from_bytes(&data).unwrap();
-
-    let data_fungible = config.client.get_account(&mule_obj.fungible_asset)?;
+    let mule = MuleContext::fetch(&config.client, &args.mule)?;
 
-    let swap_marker = Pubkey::find_program_address(
-        &[
-            b"swap_marker",
-            args.mule.as_ref(),
-            args.non_fungible_asset.as_ref(),
-        ],
-        &libreplex_mule_client::ID,
-    )
-    .0;
-
-    let fungible_source_token_account = get_associated_token_address_with_program_id(
-        &args.mule,
-        &mule_obj.fungible_asset,
-        data_fungible.owner(),
-    );
-
-    let fungible_target_token_account = get_associated_token_address_with_program_id(
+    let ix = get_swap_to_fungible_ix(
+        &config.client,
         &authority,
-        &mule_obj.fungible_asset,
-        data_fungible.owner(),
-    );
-
-    let mut remaining_accounts = vec![
-        AccountMeta {
-            pubkey: spl_token::ID,
-            is_signer: false,
-            is_writable: false,
-        },
-        AccountMeta {
-            pubkey: system_program::ID,
-            is_signer: false,
-            is_writable: false,
-        },
-        AccountMeta {
-            pubkey: MPL_CORE_ID,
-            is_signer: false,
-            is_writable: false,
-        },
-        AccountMeta {
-            pubkey: MPL_TOKEN_METADATA_ID,
-            is_signer: false,
-            is_writable: false,
-        },
-        AccountMeta {
-            pubkey: spl_associated_token_account::ID,
-            is_signer: false,
-            is_writable: false,
-        },
-        AccountMeta {
-            pubkey: AUTH_RULES_PROGRAM_ID,
-            is_signer: false,
-            is_writable: false,
-        },
-        AccountMeta {
-            pubkey: SYSVAR_INSTRUCTIONS_PROGRAM_ID,
-            is_signer: false,
-            is_writable: false,
-        },
-    ];
-
-    if let Some(x) = args.asset_group {
-        remaining_accounts.push(AccountMeta {
-            pubkey: x,
-            is_signer: false,
-            is_writable: true,
-        });
-    }
-
-    let metadata = Pubkey::find_program_address(
-        &[
-            b"metadata",
-            MPL_TOKEN_METADATA_ID.as_ref(),
-            &args.non_fungible_asset.as_ref(),
-        ],
-        &MPL_TOKEN_METADATA_ID,
-    )
-    .0;
-    if let Some(x) = args.non_fungible_source_token_account {
-        // add metadata
-        remaining_accounts.push(AccountMeta {
-            pubkey: metadata,
-            is_signer: false,
-            is_writable: true,
-        });
-
-        // add target token record
-        remaining_accounts.push(AccountMeta {
-            pubkey: Pubkey::find_program_address(
-                &[
-                    b"metadata",
-                    MPL_TOKEN_METADATA_ID.as_ref(),
-                    args.non_fungible_asset.as_ref(),
-                    b"token_record",
-                    x.as_ref(),
-                ],
-                &MPL_TOKEN_METADATA_ID,
-            )
-            .0,
-            is_signer: false,
-            is_writable: true,
-        });
-    }
-
-    let mut account_non_fungible = config.client.get_account(&args.non_fungible_asset)?;
-    let data_non_fungible = AccountInfo::new(
+        &mule,
         &args.non_fungible_asset,
-        false,
-        false,
-        &mut account_non_fungible.lamports,
-        &mut account_non_fungible.data,
-        &account_non_fungible.owner,
-        account_non_fungible.executable,
-        account_non_fungible.rent_epoch,
-    );
-    
-    // if it is a mint, then grab some metadata as well
-
-    let account_metadata = config.client.get_account(&metadata);
-    let mut metadata_data: Option<AccountInfo> = None;
-
-    let mut lamports = 0;
-    let mut data: Vec<u8> = vec![];
-    if let Some(mut md) = account_metadata.ok() {
-        if md.owner == MPL_TOKEN_METADATA_ID {
-            let metadata_obj = Metadata::from_bytes(md.data())?;
-            data.append(&mut md.data);
-            metadata_data = Some(AccountInfo::new(
-                &metadata,
-                false,
-                false,
-                &mut lamports,
-                &mut data,
-                &MPL_TOKEN_METADATA_ID,
-                md.executable,
-                md.rent_epoch,
-            ));
-            match metadata_obj.token_standard {
-                Some(x) => match &x {
-                    TokenStandard::ProgrammableNonFungible => {
-                        match metadata_obj.programmable_config {
-                            Some(x) => match &x {
-                                mpl_token_metadata::types::ProgrammableConfig::V1 { rule_set } => {
-                                    if let Some(x) = rule_set {
-                                        remaining_accounts.push(AccountMeta {
-                                            pubkey: *x,
-                                            is_signer: false,
-                                            is_writable: false,
-                                        });
-                                    }
-                                }
-                            },
-                            None => {}
-                        }
-                    }
-                    _ => {}
-                },
-                None => todo!(),
-            }
-            remaining_accounts.push(AccountMeta {
-                pubkey: metadata,
-                is_signer: false,
-                is_writable: true,
-            });
-        }
-    }
-    let nico: Nico = Nico::from_raw_data(
-        &data_non_fungible,
-        match &metadata_data {
-            Some(x) => Some(&x),
-            None => None,
-        },
-        None,
-        None,
-    );
-
-    let target_ata = get_associated_token_address_with_program_id(
-        &args.mule,
-        &args.non_fungible_asset,
-        &spl_token::ID,
-    );
-    remaining_accounts.push(AccountMeta {
-        pubkey: target_ata,
-        is_signer: false,
-        is_writable: true,
-    });
-
-    remaining_accounts.push(AccountMeta {
-        pubkey: Pubkey::find_program_address(
-            &[
-                b"metadata",
-                mpl_token_metadata::ID.as_ref(),
-                args.non_fungible_asset.as_ref(),
-                b"token_record",
-                target_ata.as_ref(),
-            ],
-            &mpl_token_metadata::ID,
-        )
-        .0,
-        is_signer: false,
-        is_writable: true,
-    });
-
-    remaining_accounts.push(AccountMeta {
-        pubkey: Pubkey::find_program_address(
-            &[
-                b"metadata",
-                &mpl_token_metadata::ID.as_ref(),
-                &args.non_fungible_asset.as_ref(),
-                b"edition",
-            ],
-            &mpl_token_metadata::ID,
-        )
-        .0,
-        is_signer: false,
-        is_writable: false,
-    });
-
-    if let Some(x) = nico.group {
-        remaining_accounts.push(AccountMeta {
-            pubkey: x,
-            is_signer: false,
-            is_writable: false,
-        });
-    }
-
-    let ix = SwapToFungible {
-        payer: authority,
-        swapper: authority,
-        mule: args.mule,
-        cosigner: None,
-        swap_marker: swap_marker,
-        non_fungible_asset: args.non_fungible_asset,
-        fungible_asset: mule_obj.fungible_asset,
-        fungible_source_token_account,
-        fungible_target_token_account,
-        non_fungible_source_token_account: args.non_fungible_source_token_account,
-        system_program: system_program::ID,
-        associated_token_program: spl_associated_token_account::ID,
-    }
-    .instruction_with_remaining_accounts(remaining_accounts.as_slice());
-
-    let signers = vec![&authority_sk];
-
-    let micro_lamports = get_priority_fee(&args.priority);
-    let compute_units = 500_000; //get_compute_units(&config.client, &[ix.clone()], &signers)?;
+        args.asset_group,
+        args.non_fungible_source_token_account,
+    )?;
 
-    let instructions = vec![
-        ComputeBudgetInstruction::set_compute_unit_limit(compute_units as u32),
-        ComputeBudgetInstruction::set_compute_unit_price(micro_lamports),
-        ix,
-    ];
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+    let signers = transaction_signers(&authority_sk, nonce_authority.as_deref());
 
-    println!("Sending transaction");
+    let lookup_tables = fetch_lookup_tables(&config.client, &args.transaction.lookup_tables)?;
 
-    let sig = send_and_confirm_tx_with_spinner_with_config(
+    let sig = execute_ixs(
         &config.client,
         &signers,
-        &instructions,
-        RpcSendTransactionConfig {
-            skip_preflight: true,
-            preflight_commitment: None,
-            encoding: None,
-            max_retries: None,
-            min_context_slot: None,
-        },
+        vec![ix],
+        &lookup_tables,
+        &args.priority,
+        &args.transaction,
     )?;
 
-    println!("Swapped asset to fungible. Tx: {sig}");
+    if let Some(sig) = sig {
+        println!("Swapped asset to fungible. Tx: {sig}");
+    }
 
     Ok(())
 }
@@ -0,0 +1,90 @@
+use super::*;
+
+use spl_associated_token_account::get_associated_token_address_with_program_id;
+
+pub struct SwapToNonFungibleArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    pub non_fungible_asset: Pubkey,
+    pub asset_group: Option<Pubkey>,
+    pub priority: Priority,
+    pub transaction: TransactionOptions,
+}
+
+pub fn handle_swap_to_non_fungible(args: SwapToNonFungibleArgs) -> Result<()> {
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let authority_sk = config.keypair;
+
+    let authority = authority_sk.pubkey();
+
+    let mule = MuleContext::fetch(&config.client, &args.mule)?;
+
+    let swap_marker = get_swap_marker(&args.mule, &args.non_fungible_asset);
+
+    // mints are escrowed in the mule's ATA, core and nifty assets are owned by the mule directly
+    let data_non_fungible = config.client.get_account(&args.non_fungible_asset)?;
+    let (asset_group, non_fungible_source_token_account) =
+        match detect_asset_kind(&args.non_fungible_asset, &data_non_fungible)? {
+            AssetKind::SplToken | AssetKind::Token2022 => (
+                args.asset_group,
+                Some(get_associated_token_address_with_program_id(
+                    &args.mule,
+                    &args.non_fungible_asset,
+                    &data_non_fungible.owner,
+                )),
+            ),
+            AssetKind::Core => (
+                args.asset_group
+                    .or_else(|| get_core_collection(&data_non_fungible)),
+                None,
+            ),
+            AssetKind::Nifty => (args.asset_group, None),
+        };
+
+    let remaining_accounts = get_swap_remaining_accounts(
+        &config.client,
+        &args.non_fungible_asset,
+        asset_group,
+        non_fungible_source_token_account,
+        &authority,
+    )?;
+
+    let ix = SwapToNonFungible {
+        payer: authority,
+        swapper: authority,
+        mule: args.mule,
+        cosigner: None,
+        swap_marker,
+        non_fungible_asset: args.non_fungible_asset,
+        fungible_asset: mule.mule.fungible_asset,
+        // fungibles go back from the swapper into the mule
+        fungible_source_token_account: mule.fungible_token_account(&authority),
+        fungible_target_token_account: mule.fungible_token_account(&args.mule),
+        non_fungible_source_token_account,
+        system_program: system_program::ID,
+        associated_token_program: spl_associated_token_account::ID,
+    }
+    .instruction_with_remaining_accounts(remaining_accounts.as_slice());
+
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+    let signers = transaction_signers(&authority_sk, nonce_authority.as_deref());
+
+    let lookup_tables = fetch_lookup_tables(&config.client, &args.transaction.lookup_tables)?;
+
+    let sig = execute_ixs(
+        &config.client,
+        &signers,
+        vec![ix],
+        &lookup_tables,
+        &args.priority,
+        &args.transaction,
+    )?;
+
+    if let Some(sig) = sig {
+        println!("Swapped fungible to asset. Tx: {sig}");
+    }
+
+    Ok(())
+}
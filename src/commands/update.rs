@@ -0,0 +1,223 @@
+use std::io::{self, BufRead, Write};
+
+use libreplex_mule_client::{
+    instructions::{UpdateMule, UpdateMuleInstructionArgs},
+    types::Filter,
+};
+
+use crate::amount::parse_and_echo_ui_amount;
+
+use super::*;
+
+pub struct UpdateArgs {
+    pub keypair_path: Option<PathBuf>,
+    pub rpc_url: Option<String>,
+    pub mule: Pubkey,
+    /// UI amount, e.g. 1000.5
+    pub base_swap_rate: Option<String>,
+    pub auto_generate_swap_marker: Option<bool>,
+    pub filter: Option<Filter>,
+    pub update_auth: Option<Pubkey>,
+    pub fee_per_swap_lamports: Option<u64>,
+    pub fee_rate_per_swap_basis_points: Option<u16>,
+    pub swap_fee_treasury: Option<Pubkey>,
+    /// UI amount
+    pub fee_per_swap_spl_amount: Option<String>,
+    pub burn_spl_basis_points: Option<u16>,
+    pub name: Option<String>,
+    pub clear_update_auth: bool,
+    pub clear_fee_per_swap_lamports: bool,
+    pub clear_fee_rate_per_swap_basis_points: bool,
+    pub clear_swap_fee_treasury: bool,
+    pub clear_fee_per_swap_spl_amount: bool,
+    pub clear_burn_spl_basis_points: bool,
+    pub yes: bool,
+    pub priority: Priority,
+    pub transaction: TransactionOptions,
+    pub multisig: MultisigOptions,
+}
+
+/// Asks the user to confirm on stdin. Anything but `y` / `yes` declines.
+///
+/// The prompt goes to stderr, so that it is shown when stdout is redirected.
+pub fn confirm(prompt: &str) -> Result<bool> {
+    eprint!("{prompt} [y/N] ");
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
+
+pub fn handle_update(mut args: UpdateArgs) -> Result<()> {
+    // stdout is kept for the instruction bundle
+    args.transaction.progress_to_stderr = args.multisig.prints_bundle();
+
+    let config = CliConfig::new(args.keypair_path, args.rpc_url)?;
+
+    let authority_sk = config.keypair;
+
+    let signer = authority_sk.pubkey();
+
+    // with a multisig, its vault is the update authority
+    let authority = args.multisig.authority().unwrap_or(signer);
+
+    let data = config.client.get_account_data(&args.mule)?;
+    let current = Mule::from_bytes(&data)?;
+
+    let update_auth = current.update_auth.unwrap_or(current.authority);
+    if update_auth != authority {
+        return Err(anyhow!(
+            "Mule {} can only be updated by {update_auth}, not {authority}. Pass --multisig or --vault if it is a multisig vault",
+            args.mule
+        ));
+    }
+
+    let mint = MintInfo::fetch(&config.client, &current.fungible_asset)?;
+
+    let mut updated = current.clone();
+    if let Some(x) = args.base_swap_rate {
+        updated.base_swap_rate =
+            parse_and_echo_ui_amount("Base swap rate", &x, mint.decimals, &args.transaction)?;
+    }
+    if let Some(x) = args.auto_generate_swap_marker {
+        updated.auto_generate_swap_marker = x;
+    }
+    if let Some(x) = args.filter {
+        updated.filter = x;
+    }
+    if let Some(x) = args.update_auth {
+        updated.update_auth = Some(x);
+    }
+    if let Some(x) = args.fee_per_swap_lamports {
+        updated.fee_per_swap_lamports = Some(x);
+    }
+    if let Some(x) = args.fee_rate_per_swap_basis_points {
+        updated.fee_rate_per_swap_basis_points = Some(x);
+    }
+    if let Some(x) = args.swap_fee_treasury {
+        updated.swap_fee_treasury = Some(x);
+    }
+    if let Some(x) = args.fee_per_swap_spl_amount {
+        updated.fee_per_swap_spl_amount = Some(parse_and_echo_ui_amount(
+            "Fee per swap (SPL)",
+            &x,
+            mint.decimals,
+            &args.transaction,
+        )?);
+    }
+    if let Some(x) = args.burn_spl_basis_points {
+        updated.burn_spl_basis_points = Some(x);
+    }
+    if let Some(x) = args.name {
+        updated.name = x;
+    }
+
+    if args.clear_update_auth {
+        updated.update_auth = None;
+    }
+    if args.clear_fee_per_swap_lamports {
+        updated.fee_per_swap_lamports = None;
+    }
+    if args.clear_fee_rate_per_swap_basis_points {
+        updated.fee_rate_per_swap_basis_points = None;
+    }
+    if args.clear_swap_fee_treasury {
+        updated.swap_fee_treasury = None;
+    }
+    if args.clear_fee_per_swap_spl_amount {
+        updated.fee_per_swap_spl_amount = None;
+    }
+    if args.clear_burn_spl_basis_points {
+        updated.burn_spl_basis_points = None;
+    }
+
+    let before = MuleView::new(&args.mule, &current).rows();
+    let after = MuleView::new(&args.mule, &updated).rows();
+
+    let changes: Vec<_> = before
+        .iter()
+        .zip(after.iter())
+        .filter(|(old, new)| old.1 != new.1)
+        .collect();
+
+    if changes.is_empty() {
+        args.transaction.progress("Nothing to update");
+        return Ok(());
+    }
+
+    args.transaction
+        .progress(format!("Updating mule {}:", args.mule));
+    for ((field, old), (_, new)) in &changes {
+        let new = match new.as_str() {
+            "-" => "cleared",
+            x => x,
+        };
+        args.transaction
+            .progress(format!("  {field}: {old} -> {new}"));
+    }
+
+    if !args.yes
+        && !args.transaction.dry_run
+        && !args.transaction.sign_only
+        && !confirm("Send update?")?
+    {
+        args.transaction.progress("Update cancelled");
+        return Ok(());
+    }
+
+    let ix_args = UpdateMuleInstructionArgs {
+        base_swap_rate: updated.base_swap_rate,
+        update_auth: updated.update_auth,
+        auto_generate_swap_marker: updated.auto_generate_swap_marker,
+        filter: updated.filter,
+        fee_per_swap_lamports: updated.fee_per_swap_lamports,
+        fee_rate_per_swap_basis_points: updated.fee_rate_per_swap_basis_points,
+        swap_fee_treasury: updated.swap_fee_treasury,
+        fee_per_swap_spl_amount: updated.fee_per_swap_spl_amount,
+        name: updated.name,
+        burn_spl_basis_points: updated.burn_spl_basis_points,
+    };
+
+    let ix = UpdateMule {
+        payer: authority,
+        update_auth: authority,
+        mule: args.mule,
+        system_program: system_program::ID,
+    }
+    .instruction(ix_args);
+
+    let nonce_authority = args.transaction.read_nonce_authority()?;
+    let signers = transaction_signers(&authority_sk, nonce_authority.as_deref());
+
+    let lookup_tables = fetch_lookup_tables(&config.client, &args.transaction.lookup_tables)?;
+
+    if authority != signer {
+        return propose_admin_ixs(
+            &config.client,
+            &signers,
+            &[ix],
+            &lookup_tables,
+            &args.priority,
+            &args.transaction,
+            &args.multisig,
+            &format!("Update mule {}", args.mule),
+        );
+    }
+
+    let sig = execute_ixs(
+        &config.client,
+        &signers,
+        vec![ix],
+        &lookup_tables,
+        &args.priority,
+        &args.transaction,
+    )?;
+
+    if let Some(sig) = sig {
+        println!("Mule {} updated in tx: {sig}", args.mule);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,195 @@
+use super::*;
+
+use std::str::FromStr;
+
+use solana_account_decoder::{UiAccountData, UiAccountEncoding, UiDataSliceConfig};
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcAccountInfoConfig, RpcProgramAccountsConfig},
+    rpc_filter::{Memcmp, RpcFilterType},
+    rpc_request::TokenAccountsFilter,
+};
+use solana_sdk::account::Account;
+
+/// Core `Key::AssetV1` and Nifty `Discriminator::Asset`.
+const ASSET_DISCRIMINATOR: u8 = 1;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize)]
+pub enum AssetKind {
+    SplToken,
+    Token2022,
+    Core,
+    Nifty,
+}
+
+/// A non-fungible held by a wallet.
+#[derive(Debug, Clone)]
+pub struct WalletAsset {
+    pub asset: Pubkey,
+    pub kind: AssetKind,
+    /// Token account holding the asset, for mints only
+    pub token_account: Option<Pubkey>,
+}
+
+fn get_token_non_fungibles(
+    client: &RpcClient,
+    owner: &Pubkey,
+    token_program: Pubkey,
+    kind: AssetKind,
+) -> Result<Vec<WalletAsset>> {
+    let accounts =
+        client.get_token_accounts_by_owner(owner, TokenAccountsFilter::ProgramId(token_program))?;
+
+    let mut assets = vec![];
+
+    for keyed in accounts {
+        let UiAccountData::Json(parsed) = &keyed.account.data else {
+            continue;
+        };
+
+        let info = &parsed.parsed["info"];
+        let amount = info["tokenAmount"]["amount"].as_str();
+        let decimals = info["tokenAmount"]["decimals"].as_u64();
+
+        // non-fungibles are whole single tokens
+        if amount != Some("1") || decimals != Some(0) {
+            continue;
+        }
+
+        let Some(mint) = info["mint"].as_str() else {
+            continue;
+        };
+
+        assets.push(WalletAsset {
+            asset: Pubkey::from_str(mint)?,
+            kind,
+            token_account: Some(Pubkey::from_str(&keyed.pubkey)?),
+        });
+    }
+
+    Ok(assets)
+}
+
+fn get_asset_program_non_fungibles(
+    client: &RpcClient,
+    owner: &Pubkey,
+    program_id: Pubkey,
+    owner_offset: usize,
+    kind: AssetKind,
+) -> Result<Vec<WalletAsset>> {
+    let accounts = client.get_program_accounts_with_config(
+        &program_id,
+        RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(0, vec![ASSET_DISCRIMINATOR])),
+                RpcFilterType::Memcmp(Memcmp::new_raw_bytes(owner_offset, owner.to_bytes().to_vec())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                // only the addresses are needed
+                data_slice: Some(UiDataSliceConfig {
+                    offset: 0,
+                    length: 0,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+    )?;
+
+    Ok(accounts
+        .into_iter()
+        .map(|(asset, _)| WalletAsset {
+            asset,
+            kind,
+            token_account: None,
+        })
+        .collect())
+}
+
+/// Lists the SPL Token, Token-2022, Core and Nifty non-fungibles held by `owner`.
+pub fn get_wallet_non_fungibles(client: &RpcClient, owner: &Pubkey) -> Result<Vec<WalletAsset>> {
+    let mut assets = get_token_non_fungibles(client, owner, spl_token::ID, AssetKind::SplToken)?;
+    assets.extend(get_token_non_fungibles(
+        client,
+        owner,
+        spl_token_2022::ID,
+        AssetKind::Token2022,
+    )?);
+    assets.extend(get_asset_program_non_fungibles(
+        client,
+        owner,
+        MPL_CORE_ID,
+        CORE_OWNER_OFFSET,
+        AssetKind::Core,
+    )?);
+    assets.extend(get_asset_program_non_fungibles(
+        client,
+        owner,
+        NIFTY_ASSET_ID,
+        NIFTY_OWNER_OFFSET,
+        AssetKind::Nifty,
+    )?);
+
+    Ok(assets)
+}
+
+/// Core `UpdateAuthority::Collection` variant, stored right after the owner.
+const CORE_UPDATE_AUTHORITY_COLLECTION: u8 = 2;
+
+/// Detects the standard of a non-fungible from the program owning its account.
+pub fn detect_asset_kind(asset: &Pubkey, account: &Account) -> Result<AssetKind> {
+    match account.owner {
+        x if x == spl_token::ID => Ok(AssetKind::SplToken),
+        x if x == spl_token_2022::ID => Ok(AssetKind::Token2022),
+        x if x == MPL_CORE_ID => Ok(AssetKind::Core),
+        x if x == NIFTY_ASSET_ID => Ok(AssetKind::Nifty),
+        x => Err(anyhow!(
+            "Asset {asset} is owned by unsupported program {x}"
+        )),
+    }
+}
+
+/// Returns the collection of a Core asset, if its update authority is a collection.
+pub fn get_core_collection(account: &Account) -> Option<Pubkey> {
+    let offset = CORE_OWNER_OFFSET + 32;
+
+    match account.data.get(offset) {
+        Some(&CORE_UPDATE_AUTHORITY_COLLECTION) => account
+            .data
+            .get(offset + 1..offset + 33)
+            .and_then(|x| Pubkey::try_from(x).ok()),
+        _ => None,
+    }
+}
+
+/// Finds the token account of `owner` holding the largest amount of `mint`,
+/// whether it is the associated token account or not.
+pub fn find_largest_token_account(
+    client: &RpcClient,
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> Result<Pubkey> {
+    let accounts = client.get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(*mint))?;
+
+    let mut largest: Option<(u64, Pubkey)> = None;
+
+    for keyed in accounts {
+        let UiAccountData::Json(parsed) = &keyed.account.data else {
+            continue;
+        };
+
+        let amount = parsed.parsed["info"]["tokenAmount"]["amount"]
+            .as_str()
+            .and_then(|x| x.parse::<u64>().ok())
+            .unwrap_or(0);
+
+        if amount > 0 && largest.map(|(x, _)| amount > x).unwrap_or(true) {
+            largest = Some((amount, Pubkey::from_str(&keyed.pubkey)?));
+        }
+    }
+
+    largest
+        .map(|(_, x)| x)
+        .ok_or_else(|| anyhow!("No token account of {owner} holds {mint}"))
+}
@@ -0,0 +1,24 @@
+use libreplex_mule_client::errors::LibreplexMuleError;
+use num_traits::FromPrimitive;
+use solana_program::instruction::{Instruction, InstructionError};
+use solana_sdk::transaction::TransactionError;
+
+/// Returns a readable description of `err`, decoding custom errors raised by
+/// the mule program into their error messages.
+///
+/// `ixs` are the instructions of the failed transaction, in order.
+pub fn describe_transaction_error(ixs: &[Instruction], err: &TransactionError) -> String {
+    if let TransactionError::InstructionError(index, InstructionError::Custom(code)) = err {
+        let program_id = ixs.get(*index as usize).map(|ix| ix.program_id);
+
+        if program_id == Some(libreplex_mule_client::ID) {
+            if let Some(mule_err) = LibreplexMuleError::from_u32(*code) {
+                return format!("{mule_err} (mule error {code} in instruction {index})");
+            }
+        }
+
+        return format!("Custom program error {code:#x} in instruction {index}");
+    }
+
+    err.to_string()
+}
@@ -0,0 +1,91 @@
+use anyhow::{anyhow, Result};
+use libreplex_mule_client::types;
+use solana_sdk::pubkey::Pubkey;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    str::FromStr,
+};
+
+/// Command line representation of the on-chain mule `Filter`.
+///
+/// Parses `a` / `all`, `g:<pubkey>` / `group:<pubkey>` and `c:<pubkey>` / `creator:<pubkey>`
+/// and displays using the short forms.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Filter(pub types::Filter);
+
+impl Filter {
+    /// Checks whether an asset in `group` with the verified `creators` passes this filter.
+    pub fn accepts(&self, group: Option<Pubkey>, creators: &[Pubkey]) -> bool {
+        match &self.0 {
+            types::Filter::All => true,
+            types::Filter::Group { group_id } => group == Some(*group_id),
+            types::Filter::Creator { creator_id } => creators.contains(creator_id),
+        }
+    }
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self(types::Filter::All)
+    }
+}
+
+impl From<types::Filter> for Filter {
+    fn from(filter: types::Filter) -> Self {
+        Self(filter)
+    }
+}
+
+impl From<Filter> for types::Filter {
+    fn from(filter: Filter) -> Self {
+        filter.0
+    }
+}
+
+impl FromStr for Filter {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        const USAGE: &str =
+            "Filter form is 'a' (all), 'g:<pubkey>' (group) or 'c:<pubkey>' (creator)";
+
+        let (filter_type, key) = match s.split_once(':') {
+            Some((filter_type, key)) => (filter_type, Some(key)),
+            None => (s, None),
+        };
+
+        let parse_key = |name: &str| -> Result<Pubkey> {
+            let key =
+                key.ok_or_else(|| anyhow!("Missing {name} pubkey in filter '{s}'. {USAGE}"))?;
+            Pubkey::from_str(key)
+                .map_err(|_| anyhow!("Invalid {name} pubkey '{key}' in filter '{s}'. {USAGE}"))
+        };
+
+        let filter = match filter_type.to_lowercase().as_str() {
+            "a" | "all" => match key {
+                None => types::Filter::All,
+                Some(_) => return Err(anyhow!("Filter 'all' does not take a pubkey. {USAGE}")),
+            },
+            "g" | "group" => types::Filter::Group {
+                group_id: parse_key("group")?,
+            },
+            "c" | "creator" => types::Filter::Creator {
+                creator_id: parse_key("creator")?,
+            },
+            _ => return Err(anyhow!("Invalid filter type '{filter_type}'. {USAGE}")),
+        };
+
+        Ok(Self(filter))
+    }
+}
+
+impl Display for Filter {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            types::Filter::All => write!(f, "a"),
+            types::Filter::Group { group_id } => write!(f, "g:{}", group_id),
+            types::Filter::Creator { creator_id } => write!(f, "c:{}", creator_id),
+        }
+    }
+}
@@ -1,4 +1,11 @@
+pub mod amount;
 pub mod args;
 pub mod commands;
+pub mod errors;
+pub mod filter;
+pub mod multisig;
+pub mod offline;
+pub mod output;
 pub mod setup;
+pub mod simulation;
 pub mod transaction;
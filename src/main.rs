@@ -1,9 +1,8 @@
 use anyhow::Result;
 use clap::Parser;
 
-use libreplex_mule_client::types::Filter;
 use mule_cli::{
-    args::{Args, Commands},
+    args::{Args, Commands, LutCommands, NonceCommands, SwapMarkerCommands},
     commands::*,
 };
 
@@ -15,6 +14,7 @@ async fn main() -> Result<()> {
 
     let keypair_path = args.keypair_path.clone();
     let rpc_url = args.rpc_url.clone();
+    let transaction = args.transaction.clone();
 
     match args.command {
         Commands::Create {
@@ -30,6 +30,9 @@ async fn main() -> Result<()> {
             fee_per_swap_spl_amount,
             burn_spl_basis_points,
             name,
+            seed,
+            seed_keypair,
+            multisig,
         } => handle_create(CreateArgs {
             keypair_path,
             rpc_url,
@@ -41,16 +44,206 @@ async fn main() -> Result<()> {
             swap_fee_treasury,
             burn_spl_basis_points,
             fee_per_swap_spl_amount,
-            filter: match filter {
-                mule_cli::transaction::Filter::All => Filter::All,
-                mule_cli::transaction::Filter::Group { group_id } => Filter::Group { group_id },
-                mule_cli::transaction::Filter::Creator { creator_id } => {
-                    Filter::Creator { creator_id }
-                }
-            },
+            filter: filter.into(),
             fungible_mint,
             priority,
-            name
+            transaction,
+            multisig,
+            name,
+            seed,
+            seed_keypair,
+        }),
+        Commands::Update {
+            mule,
+            base_swap_rate,
+            auto_generate_swap_marker,
+            update_auth,
+            filter,
+            fee_per_swap_lamports,
+            fee_rate_per_swap_basis_points,
+            swap_fee_treasury,
+            fee_per_swap_spl_amount,
+            burn_spl_basis_points,
+            name,
+            clear_update_auth,
+            clear_fee_per_swap_lamports,
+            clear_fee_rate_per_swap_basis_points,
+            clear_swap_fee_treasury,
+            clear_fee_per_swap_spl_amount,
+            clear_burn_spl_basis_points,
+            yes,
+            priority,
+            multisig,
+        } => handle_update(UpdateArgs {
+            keypair_path,
+            rpc_url,
+            mule,
+            base_swap_rate,
+            auto_generate_swap_marker,
+            filter: filter.map(Into::into),
+            update_auth,
+            fee_per_swap_lamports,
+            fee_rate_per_swap_basis_points,
+            swap_fee_treasury,
+            fee_per_swap_spl_amount,
+            burn_spl_basis_points,
+            name,
+            clear_update_auth,
+            clear_fee_per_swap_lamports,
+            clear_fee_rate_per_swap_basis_points,
+            clear_swap_fee_treasury,
+            clear_fee_per_swap_spl_amount,
+            clear_burn_spl_basis_points,
+            yes,
+            priority,
+            transaction,
+            multisig,
+        }),
+        Commands::DeriveAddress { seed, seed_keypair } => {
+            handle_derive_address(DeriveAddressArgs { seed, seed_keypair })
+        }
+        Commands::Show { mule, output } => handle_show(ShowArgs {
+            keypair_path,
+            rpc_url,
+            mule,
+            output,
+        }),
+        Commands::List {
+            authority,
+            fungible_mint,
+            filter_type,
+            name,
+            output,
+        } => handle_list(ListArgs {
+            keypair_path,
+            rpc_url,
+            authority,
+            fungible_mint,
+            filter_type,
+            name,
+            output,
+        }),
+        Commands::SwapMarker { command } => match command {
+            SwapMarkerCommands::Show {
+                mule,
+                asset,
+                output,
+            } => handle_swap_marker_show(SwapMarkerShowArgs {
+                keypair_path,
+                rpc_url,
+                mule,
+                asset,
+                output,
+            }),
+            SwapMarkerCommands::List { mule, output } => {
+                handle_swap_marker_list(SwapMarkerListArgs {
+                    keypair_path,
+                    rpc_url,
+                    mule,
+                    output,
+                })
+            }
+        },
+        Commands::Eligible {
+            mule,
+            owner,
+            output,
+        } => handle_eligible(EligibleArgs {
+            keypair_path,
+            rpc_url,
+            mule,
+            owner,
+            output,
+        }),
+        Commands::Fund {
+            mule,
+            amount,
+            priority,
+        } => handle_fund(FundArgs {
+            keypair_path,
+            rpc_url,
+            mule,
+            amount,
+            priority,
+            transaction,
+        }),
+        Commands::Status { mule, output } => handle_status(StatusArgs {
+            keypair_path,
+            rpc_url,
+            mule,
+            output,
+        }),
+        Commands::Lut { command } => match command {
+            LutCommands::Create {
+                mule,
+                addresses,
+                priority,
+            } => handle_lut_create(LutCreateArgs {
+                keypair_path,
+                rpc_url,
+                mule,
+                addresses,
+                priority,
+                transaction,
+            }),
+            LutCommands::Extend {
+                lookup_table,
+                mule,
+                addresses,
+                priority,
+            } => handle_lut_extend(LutExtendArgs {
+                keypair_path,
+                rpc_url,
+                lookup_table,
+                mule,
+                addresses,
+                priority,
+                transaction,
+            }),
+            LutCommands::Show {
+                lookup_table,
+                output,
+            } => handle_lut_show(LutShowArgs {
+                keypair_path,
+                rpc_url,
+                lookup_table,
+                output,
+            }),
+        },
+        Commands::Nonce { command } => match command {
+            NonceCommands::Create {
+                nonce_keypair,
+                authority,
+                priority,
+            } => handle_nonce_create(NonceCreateArgs {
+                keypair_path,
+                rpc_url,
+                nonce_keypair,
+                authority,
+                priority,
+                transaction,
+            }),
+            NonceCommands::Show { address, output } => handle_nonce_show(NonceShowArgs {
+                keypair_path,
+                rpc_url,
+                address,
+                output,
+            }),
+        },
+        Commands::Sign {
+            file,
+            signers,
+            output,
+        } => handle_sign(SignArgs {
+            keypair_path,
+            file,
+            signers,
+            output,
+        }),
+        Commands::Broadcast { file } => handle_broadcast(BroadcastArgs {
+            rpc_url,
+            file,
+            transaction,
         }),
         Commands::SwapToFungible {
             priority,
@@ -66,6 +259,39 @@ async fn main() -> Result<()> {
             non_fungible_asset,
             non_fungible_source_token_account,
             priority,
+            transaction,
+        }),
+        Commands::BatchSwapToFungible {
+            priority,
+            mule,
+            assets_file,
+            all_eligible,
+            concurrency,
+            report,
+        } => handle_batch_swap_to_fungible(BatchSwapToFungibleArgs {
+            keypair_path,
+            rpc_url,
+            mule,
+            assets_file,
+            all_eligible,
+            concurrency,
+            report,
+            priority,
+            transaction,
+        }),
+        Commands::SwapToNonFungible {
+            priority,
+            mule,
+            asset_group,
+            non_fungible_asset,
+        } => handle_swap_to_non_fungible(SwapToNonFungibleArgs {
+            keypair_path,
+            rpc_url,
+            mule,
+            asset_group,
+            non_fungible_asset,
+            priority,
+            transaction,
         }),
     }
 }
@@ -0,0 +1,334 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use clap::Args;
+use serde::Serialize;
+use solana_client::rpc_client::RpcClient;
+use solana_program::{
+    hash::hash,
+    instruction::{AccountMeta, Instruction},
+    pubkey,
+};
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount, message::Message, pubkey::Pubkey,
+    signer::Signer, system_program,
+};
+
+use crate::{
+    output::print_json,
+    setup::CliSigner,
+    transaction::{execute_ixs, Priority, TransactionOptions},
+};
+
+/// Squads v4 multisig program.
+pub const SQUADS_PROGRAM_ID: Pubkey = pubkey!("SQDS4ep65T869zMMBKyuUq6aD6EgTu8psMjkvj52pCf");
+
+const SEED_PREFIX: &[u8] = b"multisig";
+const SEED_VAULT: &[u8] = b"vault";
+const SEED_TRANSACTION: &[u8] = b"transaction";
+const SEED_PROPOSAL: &[u8] = b"proposal";
+
+/// Offset of `transaction_index` in the multisig account: discriminator,
+/// create_key, config_authority, threshold and time_lock.
+const TRANSACTION_INDEX_OFFSET: usize = 8 + 32 + 32 + 2 + 4;
+
+/// Options routing mule administration through a multisig instead of the local keypair.
+///
+/// Only `create` and `update` accept them, as the other commands are not admin instructions.
+#[derive(Args, Debug, Clone, Default, Eq, PartialEq)]
+pub struct MultisigOptions {
+    /// Squads v4 multisig administering the mule. Admin instructions are proposed to it,
+    /// with its vault as authority and payer, instead of being signed locally
+    #[arg(long, conflicts_with = "vault")]
+    pub multisig: Option<Pubkey>,
+
+    /// Index of the Squads vault acting as mule authority
+    #[arg(long, default_value_t = 0, requires = "multisig")]
+    pub vault_index: u8,
+
+    /// Multisig vault acting as mule authority. Prints the admin instructions as a JSON bundle
+    /// instead of sending them, for import into other multisig tools
+    #[arg(long)]
+    pub vault: Option<Pubkey>,
+}
+
+impl MultisigOptions {
+    /// The vault acting as mule authority, if admin instructions go through a multisig.
+    pub fn authority(&self) -> Option<Pubkey> {
+        match (&self.multisig, &self.vault) {
+            (Some(multisig), _) => Some(get_vault_address(multisig, self.vault_index)),
+            (None, Some(vault)) => Some(*vault),
+            (None, None) => None,
+        }
+    }
+
+    /// Whether the admin instructions are printed as an [`InstructionBundle`] on stdout.
+    pub fn prints_bundle(&self) -> bool {
+        self.multisig.is_none() && self.vault.is_some()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct AccountMetaView {
+    pub pubkey: String,
+    pub is_signer: bool,
+    pub is_writable: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct InstructionView {
+    pub program_id: String,
+    pub accounts: Vec<AccountMetaView>,
+    /// Base64 encoded instruction data
+    pub data: String,
+}
+
+/// Instructions to be executed by a multisig vault.
+#[derive(Debug, Serialize)]
+pub struct InstructionBundle {
+    pub authority: String,
+    pub instructions: Vec<InstructionView>,
+}
+
+impl InstructionBundle {
+    pub fn new(authority: &Pubkey, ixs: &[Instruction]) -> Self {
+        Self {
+            authority: authority.to_string(),
+            instructions: ixs
+                .iter()
+                .map(|ix| InstructionView {
+                    program_id: ix.program_id.to_string(),
+                    accounts: ix
+                        .accounts
+                        .iter()
+                        .map(|x| AccountMetaView {
+                            pubkey: x.pubkey.to_string(),
+                            is_signer: x.is_signer,
+                            is_writable: x.is_writable,
+                        })
+                        .collect(),
+                    data: STANDARD.encode(&ix.data),
+                })
+                .collect(),
+        }
+    }
+}
+
+pub fn get_vault_address(multisig: &Pubkey, vault_index: u8) -> Pubkey {
+    Pubkey::find_program_address(
+        &[SEED_PREFIX, multisig.as_ref(), SEED_VAULT, &[vault_index]],
+        &SQUADS_PROGRAM_ID,
+    )
+    .0
+}
+
+pub fn get_transaction_address(multisig: &Pubkey, transaction_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+        ],
+        &SQUADS_PROGRAM_ID,
+    )
+    .0
+}
+
+pub fn get_proposal_address(multisig: &Pubkey, transaction_index: u64) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            SEED_PREFIX,
+            multisig.as_ref(),
+            SEED_TRANSACTION,
+            &transaction_index.to_le_bytes(),
+            SEED_PROPOSAL,
+        ],
+        &SQUADS_PROGRAM_ID,
+    )
+    .0
+}
+
+/// Anchor instruction discriminator: the first 8 bytes of `sha256("global:<name>")`.
+fn instruction_discriminator(name: &str) -> [u8; 8] {
+    let mut discriminator = [0u8; 8];
+    discriminator.copy_from_slice(&hash(format!("global:{name}").as_bytes()).to_bytes()[..8]);
+    discriminator
+}
+
+/// Index of the last transaction created in `multisig`.
+pub fn get_transaction_index(client: &RpcClient, multisig: &Pubkey) -> Result<u64> {
+    let account = client.get_account(multisig)?;
+
+    let data = account
+        .data
+        .get(TRANSACTION_INDEX_OFFSET..TRANSACTION_INDEX_OFFSET + 8)
+        .filter(|_| account.owner == SQUADS_PROGRAM_ID)
+        .ok_or_else(|| anyhow!("{multisig} is not a Squads v4 multisig"))?;
+
+    Ok(u64::from_le_bytes(data.try_into().unwrap()))
+}
+
+/// Serializes `ixs` as the Squads `TransactionMessage` executed by `vault`.
+///
+/// The layout is that of a legacy message, with `u8` length prefixes and a
+/// `u16` one for instruction data. Lookup tables are not used.
+pub fn vault_transaction_message(vault: &Pubkey, ixs: &[Instruction]) -> Result<Vec<u8>> {
+    let message = Message::new(ixs, Some(vault));
+    let header = message.header;
+
+    let len_u8 = |len: usize| {
+        u8::try_from(len).map_err(|_| anyhow!("Vault transaction is too large for a proposal"))
+    };
+
+    let num_signers = header.num_required_signatures;
+    let mut data = vec![
+        num_signers,
+        num_signers - header.num_readonly_signed_accounts,
+        len_u8(message.account_keys.len())? - num_signers - header.num_readonly_unsigned_accounts,
+    ];
+
+    data.push(len_u8(message.account_keys.len())?);
+    for key in &message.account_keys {
+        data.extend_from_slice(key.as_ref());
+    }
+
+    data.push(len_u8(message.instructions.len())?);
+    for ix in &message.instructions {
+        data.push(ix.program_id_index);
+        data.push(len_u8(ix.accounts.len())?);
+        data.extend_from_slice(&ix.accounts);
+        data.extend_from_slice(
+            &u16::try_from(ix.data.len())
+                .map_err(|_| anyhow!("Vault transaction is too large for a proposal"))?
+                .to_le_bytes(),
+        );
+        data.extend_from_slice(&ix.data);
+    }
+
+    // no address table lookups
+    data.push(0);
+
+    Ok(data)
+}
+
+/// Builds the Squads instructions creating vault transaction `transaction_index`
+/// of `multisig` for `ixs`, and a proposal to approve it.
+pub fn propose_vault_transaction_ixs(
+    multisig: &Pubkey,
+    vault_index: u8,
+    transaction_index: u64,
+    creator: &Pubkey,
+    ixs: &[Instruction],
+    memo: Option<String>,
+) -> Result<Vec<Instruction>> {
+    let vault = get_vault_address(multisig, vault_index);
+    let transaction = get_transaction_address(multisig, transaction_index);
+    let proposal = get_proposal_address(multisig, transaction_index);
+
+    let message = vault_transaction_message(&vault, ixs)?;
+
+    let mut create_data = instruction_discriminator("vault_transaction_create").to_vec();
+    create_data.push(vault_index);
+    // no ephemeral signers
+    create_data.push(0);
+    create_data.extend_from_slice(&(message.len() as u32).to_le_bytes());
+    create_data.extend_from_slice(&message);
+    match memo {
+        Some(memo) => {
+            create_data.push(1);
+            create_data.extend_from_slice(&(memo.len() as u32).to_le_bytes());
+            create_data.extend_from_slice(memo.as_bytes());
+        }
+        None => create_data.push(0),
+    }
+
+    let mut proposal_data = instruction_discriminator("proposal_create").to_vec();
+    proposal_data.extend_from_slice(&transaction_index.to_le_bytes());
+    // not a draft, members can vote right away
+    proposal_data.push(0);
+
+    Ok(vec![
+        Instruction {
+            program_id: SQUADS_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new(*multisig, false),
+                AccountMeta::new(transaction, false),
+                AccountMeta::new_readonly(*creator, true),
+                AccountMeta::new(*creator, true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data: create_data,
+        },
+        Instruction {
+            program_id: SQUADS_PROGRAM_ID,
+            accounts: vec![
+                AccountMeta::new_readonly(*multisig, false),
+                AccountMeta::new(proposal, false),
+                AccountMeta::new_readonly(*creator, true),
+                AccountMeta::new(*creator, true),
+                AccountMeta::new_readonly(system_program::ID, false),
+            ],
+            data: proposal_data,
+        },
+    ])
+}
+
+/// Hands the admin instructions `ixs`, built with the multisig vault as
+/// authority, to the multisig instead of executing them.
+///
+/// With `--vault` they are printed as an [`InstructionBundle`]. With
+/// `--multisig` a Squads vault transaction and its proposal are created by the
+/// first signer, who must be a member allowed to initiate transactions.
+#[allow(clippy::too_many_arguments)]
+pub fn propose_admin_ixs(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    priority: &Priority,
+    options: &TransactionOptions,
+    multisig_options: &MultisigOptions,
+    memo: &str,
+) -> Result<()> {
+    let Some(multisig) = multisig_options.multisig else {
+        let authority = multisig_options
+            .authority()
+            .ok_or_else(|| anyhow!("One of --multisig or --vault is required"))?;
+
+        return print_json(&InstructionBundle::new(&authority, ixs));
+    };
+
+    let transaction_index = get_transaction_index(client, &multisig)? + 1;
+
+    let proposal_ixs = propose_vault_transaction_ixs(
+        &multisig,
+        multisig_options.vault_index,
+        transaction_index,
+        &signers[0].pubkey(),
+        ixs,
+        Some(memo.to_string()),
+    )?;
+
+    options.progress(format!(
+        "Proposing transaction #{transaction_index} to multisig {multisig}"
+    ));
+
+    let sig = execute_ixs(
+        client,
+        signers,
+        proposal_ixs,
+        lookup_tables,
+        priority,
+        options,
+    )?;
+
+    if let Some(sig) = sig {
+        println!(
+            "Proposal {} created in tx: {sig}",
+            get_proposal_address(&multisig, transaction_index)
+        );
+        println!("Multisig members can now approve and execute it.");
+    }
+
+    Ok(())
+}
@@ -0,0 +1,256 @@
+use anyhow::{anyhow, Result};
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Serialize};
+use solana_client::rpc_client::RpcClient;
+use solana_program::instruction::Instruction;
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount, hash::Hash, message::VersionedMessage,
+    pubkey::Pubkey, signature::Signature, transaction::VersionedTransaction,
+};
+
+use crate::{
+    setup::CliSigner,
+    transaction::{build_transaction, get_nonce_data, TransactionOptions},
+};
+
+use std::{
+    fmt::{self, Display, Formatter},
+    fs::File,
+    path::Path,
+    str::FromStr,
+};
+
+#[derive(Debug, Default, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransactionEncoding {
+    Base58,
+    #[default]
+    Base64,
+}
+
+impl FromStr for TransactionEncoding {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "base58" => Ok(Self::Base58),
+            "base64" => Ok(Self::Base64),
+            _ => Err(anyhow!(
+                "Invalid transaction encoding. Expected 'base58' or 'base64'"
+            )),
+        }
+    }
+}
+
+impl Display for TransactionEncoding {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Base58 => write!(f, "base58"),
+            Self::Base64 => write!(f, "base64"),
+        }
+    }
+}
+
+/// A required signer of an exported transaction and its signature, if already signed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EnvelopeSigner {
+    pub pubkey: String,
+    pub signature: Option<String>,
+}
+
+/// A serialized, possibly partially signed, transaction passed between the
+/// `--sign-only`, `sign` and `broadcast` steps.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionEnvelope {
+    pub encoding: TransactionEncoding,
+    pub transaction: String,
+    pub blockhash: String,
+    /// Durable nonce account the transaction advances, if any
+    pub nonce_account: Option<String>,
+    pub signers: Vec<EnvelopeSigner>,
+}
+
+impl TransactionEnvelope {
+    pub fn new(
+        tx: &VersionedTransaction,
+        encoding: TransactionEncoding,
+        nonce_account: Option<Pubkey>,
+    ) -> Result<Self> {
+        let data = bincode::serialize(tx)?;
+
+        let signers = required_signers(&tx.message)
+            .iter()
+            .zip(&tx.signatures)
+            .map(|(pubkey, signature)| EnvelopeSigner {
+                pubkey: pubkey.to_string(),
+                signature: (*signature != Signature::default()).then(|| signature.to_string()),
+            })
+            .collect();
+
+        Ok(Self {
+            encoding,
+            transaction: match encoding {
+                TransactionEncoding::Base58 => bs58::encode(data).into_string(),
+                TransactionEncoding::Base64 => STANDARD.encode(data),
+            },
+            blockhash: tx.message.recent_blockhash().to_string(),
+            nonce_account: nonce_account.map(|x| x.to_string()),
+            signers,
+        })
+    }
+
+    /// Reads an envelope from a JSON file, or a bare base58 / base64 transaction.
+    pub fn read(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let content = content.trim();
+
+        if content.starts_with('{') {
+            return serde_json::from_str(content)
+                .map_err(|err| anyhow!("{} is not a transaction envelope: {err}", path.display()));
+        }
+
+        // a bare transaction, as printed by other tools
+        let tx = decode_transaction(content, TransactionEncoding::Base64)
+            .or_else(|_| decode_transaction(content, TransactionEncoding::Base58))
+            .map_err(|_| anyhow!("{} is not a transaction", path.display()))?;
+
+        Self::new(&tx, TransactionEncoding::Base64, None)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<()> {
+        serde_json::to_writer_pretty(File::create(path)?, self)?;
+
+        Ok(())
+    }
+
+    pub fn transaction(&self) -> Result<VersionedTransaction> {
+        decode_transaction(&self.transaction, self.encoding)
+    }
+
+    pub fn nonce_account(&self) -> Result<Option<Pubkey>> {
+        self.nonce_account
+            .as_deref()
+            .map(|x| Pubkey::from_str(x).map_err(|_| anyhow!("Invalid nonce account '{x}'")))
+            .transpose()
+    }
+
+    /// Prints the signing status in the format of the `solana` CLI `--sign-only` output.
+    pub fn print_summary(&self) {
+        eprintln!("Blockhash: {}", self.blockhash);
+
+        if let Some(nonce_account) = &self.nonce_account {
+            eprintln!("Nonce account: {nonce_account}");
+        }
+
+        let (present, absent): (Vec<_>, Vec<_>) =
+            self.signers.iter().partition(|x| x.signature.is_some());
+
+        if !present.is_empty() {
+            eprintln!("Signers (Pubkey=Signature):");
+            for signer in present {
+                eprintln!(
+                    "  {}={}",
+                    signer.pubkey,
+                    signer.signature.as_deref().unwrap_or_default()
+                );
+            }
+        }
+
+        if !absent.is_empty() {
+            eprintln!("Absent Signers (Pubkey):");
+            for signer in absent {
+                eprintln!("  {}", signer.pubkey);
+            }
+        }
+    }
+}
+
+pub fn decode_transaction(
+    data: &str,
+    encoding: TransactionEncoding,
+) -> Result<VersionedTransaction> {
+    let bytes = match encoding {
+        TransactionEncoding::Base58 => bs58::decode(data).into_vec()?,
+        TransactionEncoding::Base64 => STANDARD.decode(data)?,
+    };
+
+    let tx: VersionedTransaction = bincode::deserialize(&bytes)?;
+
+    // the signer and program lookups below index the message blindly
+    tx.sanitize()
+        .map_err(|err| anyhow!("Invalid transaction: {err}"))?;
+
+    let num_required_signatures = tx.message.header().num_required_signatures as usize;
+    if tx.signatures.len() != num_required_signatures {
+        return Err(anyhow!(
+            "Invalid transaction: {} signature(s) for {num_required_signatures} required signer(s)",
+            tx.signatures.len()
+        ));
+    }
+
+    Ok(tx)
+}
+
+/// Keys that must sign `message`, in signature order.
+pub fn required_signers(message: &VersionedMessage) -> &[Pubkey] {
+    &message.static_account_keys()[..message.header().num_required_signatures as usize]
+}
+
+/// Required signers of `tx` whose signature is still missing.
+pub fn absent_signers(tx: &VersionedTransaction) -> Vec<Pubkey> {
+    required_signers(&tx.message)
+        .iter()
+        .zip(&tx.signatures)
+        .filter(|(_, signature)| **signature == Signature::default())
+        .map(|(pubkey, _)| *pubkey)
+        .collect()
+}
+
+/// Instructions of `message` reduced to their program, enough to describe errors.
+///
+/// Programs are always static keys, so no lookup table is needed.
+pub fn message_programs(message: &VersionedMessage) -> Vec<Instruction> {
+    message
+        .instructions()
+        .iter()
+        .map(|ix| Instruction {
+            program_id: message.static_account_keys()[ix.program_id_index as usize],
+            accounts: vec![],
+            data: vec![],
+        })
+        .collect()
+}
+
+/// Signs `ixs` with the available keys and prints the transaction envelope
+/// instead of sending it.
+///
+/// The envelope goes to stdout so it can be redirected to a file, the
+/// signing summary to stderr.
+pub fn sign_only_tx(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    options: &TransactionOptions,
+) -> Result<()> {
+    let blockhash: Hash = match (options.blockhash, &options.nonce_account) {
+        (Some(blockhash), _) => blockhash,
+        (None, Some(nonce_account)) => get_nonce_data(client, nonce_account)?.blockhash(),
+        (None, None) => client.get_latest_blockhash()?,
+    };
+
+    // keys given as bare public keys leave their signature empty
+    let tx = build_transaction(signers, ixs, lookup_tables, blockhash)?;
+
+    let envelope = TransactionEnvelope::new(&tx, options.tx_encoding, options.nonce_account)?;
+
+    envelope.print_summary();
+
+    if options.nonce_account.is_none() {
+        eprintln!("The blockhash expires in about a minute. Use --nonce-account to sign transactions that do not expire.");
+    }
+
+    println!("{}", serde_json::to_string_pretty(&envelope)?);
+
+    Ok(())
+}
@@ -3,12 +3,16 @@ use dirs::home_dir;
 use serde::{Deserialize, Serialize};
 use solana_client::rpc_client::RpcClient;
 use solana_sdk::{
-    clock::Slot,
     commitment_config::CommitmentConfig,
-    hash::Hash,
-    signature::{read_keypair_file, Keypair},
+    pubkey::Pubkey,
+    signature::read_keypair_file,
+    signer::{null_signer::NullSigner, Signer},
+};
+use std::{
+    fs::File,
+    path::{Path, PathBuf},
+    str::FromStr,
 };
-use std::{fs::File, path::PathBuf, str::FromStr};
 
 #[derive(Debug, Deserialize, Serialize)]
 struct SolanaConfig {
@@ -17,11 +21,12 @@ struct SolanaConfig {
     pub commitment: String,
 }
 
+/// Signer of a keypair file or, for offline signing, of a bare public key.
+pub type CliSigner = dyn Signer + Send + Sync;
+
 pub struct CliConfig {
     pub client: RpcClient,
-    pub keypair: Keypair,
-    pub recent_blockhash: Hash,
-    pub recent_slot: Slot,
+    pub keypair: Box<CliSigner>,
 }
 
 #[derive(Debug, Default)]
@@ -51,7 +56,7 @@ impl CliConfigBuilder {
         self.commitment = Some(commitment);
         self
     }
-    pub fn build(&self) -> Result<CliConfig> {
+    pub fn build_client(&self) -> Result<RpcClient> {
         let rpc_url = self
             .json_rpc_url
             .clone()
@@ -62,30 +67,31 @@ impl CliConfigBuilder {
             None => CommitmentConfig::confirmed(),
         };
 
-        let client = RpcClient::new_with_commitment(rpc_url, commitment);
-
+        Ok(RpcClient::new_with_commitment(rpc_url, commitment))
+    }
+    pub fn build_signer(&self) -> Result<Box<CliSigner>> {
         let keypair_path = self
             .keypair_path
             .clone()
             .ok_or_else(|| anyhow!("No keypair path provided"))?;
 
-        let keypair =
-            read_keypair_file(keypair_path).map_err(|_| anyhow!("Unable to read keypair file"))?;
-
-        let recent_blockhash = client.get_latest_blockhash()?;
-        let recent_slot = client.get_slot()?;
+        read_signer(&keypair_path)
+    }
+    pub fn build(&self) -> Result<CliConfig> {
+        let client = self.build_client()?;
+        let keypair = self.build_signer()?;
 
-        Ok(CliConfig {
-            client,
-            keypair,
-            recent_blockhash,
-            recent_slot,
-        })
+        Ok(CliConfig { client, keypair })
     }
 }
 
 impl CliConfig {
     pub fn new(keypair_path: Option<PathBuf>, rpc_url: Option<String>) -> Result<Self> {
+        Self::builder(keypair_path, rpc_url).build()
+    }
+
+    /// Builder seeded from the Solana CLI config, overridden by the given options.
+    pub fn builder(keypair_path: Option<PathBuf>, rpc_url: Option<String>) -> CliConfigBuilder {
         let mut builder = CliConfigBuilder::new();
         let solana_config = parse_solana_config();
 
@@ -104,18 +110,25 @@ impl CliConfig {
             builder = builder.rpc_url(rpc_url);
         }
 
-        let config = builder.build()?;
+        builder
+    }
+}
 
-        Ok(config)
+/// Reads the keypair file at `source`.
+///
+/// A public key is accepted instead of a file so that transactions can be
+/// built and partially signed for keys kept offline (see `--sign-only`).
+pub fn read_signer(source: &Path) -> Result<Box<CliSigner>> {
+    if !source.exists() {
+        if let Some(pubkey) = source.to_str().and_then(|x| Pubkey::from_str(x).ok()) {
+            return Ok(Box::new(NullSigner::new(&pubkey)));
+        }
     }
 
-    #[allow(unused)]
-    pub fn update_blocks(&mut self) -> Result<()> {
-        self.recent_blockhash = self.client.get_latest_blockhash()?;
-        self.recent_slot = self.client.get_slot()?;
+    let keypair = read_keypair_file(source)
+        .map_err(|_| anyhow!("Unable to read keypair file {}", source.display()))?;
 
-        Ok(())
-    }
+    Ok(Box::new(keypair))
 }
 
 fn parse_solana_config() -> Option<SolanaConfig> {
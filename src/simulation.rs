@@ -0,0 +1,137 @@
+use anyhow::{anyhow, Result};
+use solana_account_decoder::UiAccountEncoding;
+use solana_client::{
+    rpc_client::RpcClient,
+    rpc_config::{RpcSimulateTransactionAccountsConfig, RpcSimulateTransactionConfig},
+};
+use solana_program::instruction::Instruction;
+use solana_sdk::{
+    account::Account, address_lookup_table::AddressLookupTableAccount, pubkey::Pubkey,
+};
+
+use crate::{errors::describe_transaction_error, setup::CliSigner, transaction::message_accounts};
+
+/// Size of the base SPL token account layout, shared by Token-2022.
+const TOKEN_ACCOUNT_LEN: usize = 165;
+/// Token-2022 `AccountType::Account` marker stored right after the base layout.
+const TOKEN_2022_ACCOUNT_TYPE: u8 = 2;
+
+/// Returns the token amount held by `account` if it is an SPL Token or Token-2022 token account.
+pub fn token_account_amount(account: &Account) -> Option<u64> {
+    if account.owner != spl_token::ID && account.owner != spl_token_2022::ID {
+        return None;
+    }
+
+    let is_token_account = account.data.len() == TOKEN_ACCOUNT_LEN
+        || (account.data.len() > TOKEN_ACCOUNT_LEN
+            && account.data[TOKEN_ACCOUNT_LEN] == TOKEN_2022_ACCOUNT_TYPE);
+
+    if !is_token_account {
+        return None;
+    }
+
+    let mut amount = [0u8; 8];
+    amount.copy_from_slice(&account.data[64..72]);
+
+    Some(u64::from_le_bytes(amount))
+}
+
+/// Builds and signs the transaction exactly as it would be sent, simulates it and
+/// prints the outcome without broadcasting.
+pub fn simulate_tx(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<()> {
+    let tx = crate::transaction!(client, signers, ixs, lookup_tables);
+    let accounts = message_accounts(&tx.message, lookup_tables)?;
+
+    let writable: Vec<Pubkey> = accounts
+        .iter()
+        .filter(|(_, is_writable, _)| *is_writable)
+        .map(|(key, _, _)| *key)
+        .collect();
+
+    let pre_accounts = client.get_multiple_accounts(&writable)?;
+
+    let result = client
+        .simulate_transaction_with_config(
+            &tx,
+            RpcSimulateTransactionConfig {
+                sig_verify: false,
+                replace_recent_blockhash: true,
+                commitment: Some(client.commitment()),
+                accounts: Some(RpcSimulateTransactionAccountsConfig {
+                    encoding: Some(UiAccountEncoding::Base64),
+                    addresses: writable.iter().map(|x| x.to_string()).collect(),
+                }),
+                ..Default::default()
+            },
+        )?
+        .value;
+
+    println!("Accounts:");
+    for (i, (key, is_writable, is_signer)) in accounts.iter().enumerate() {
+        println!(
+            "  {:>2} {key} {}{}",
+            i,
+            if *is_writable { "w" } else { "-" },
+            if *is_signer { "s" } else { "-" },
+        );
+    }
+
+    println!("Logs:");
+    for log in result.logs.unwrap_or_default() {
+        println!("  {log}");
+    }
+
+    match result.units_consumed {
+        Some(units) => println!("Compute units consumed: {units}"),
+        None => println!("Compute units consumed: unknown"),
+    }
+
+    let post_accounts = result.accounts.unwrap_or_default();
+
+    println!("Balance changes:");
+    for (i, key) in writable.iter().enumerate() {
+        let pre = pre_accounts.get(i).cloned().flatten();
+        let post = post_accounts
+            .get(i)
+            .and_then(|x| x.as_ref())
+            .and_then(|x| x.decode::<Account>());
+
+        let pre_lamports = pre.as_ref().map(|x| x.lamports).unwrap_or(0);
+        let post_lamports = post.as_ref().map(|x| x.lamports).unwrap_or(0);
+        if pre_lamports != post_lamports {
+            println!(
+                "  {key} lamports: {pre_lamports} -> {post_lamports} ({:+})",
+                post_lamports as i128 - pre_lamports as i128
+            );
+        }
+
+        let pre_amount = pre.as_ref().and_then(token_account_amount);
+        let post_amount = post.as_ref().and_then(token_account_amount);
+        if pre_amount.is_some() || post_amount.is_some() {
+            let pre_amount = pre_amount.unwrap_or(0);
+            let post_amount = post_amount.unwrap_or(0);
+            if pre_amount != post_amount {
+                println!(
+                    "  {key} tokens: {pre_amount} -> {post_amount} ({:+})",
+                    post_amount as i128 - pre_amount as i128
+                );
+            }
+        }
+    }
+
+    match result.err {
+        Some(err) => Err(anyhow!(
+            "Simulation failed: {}",
+            describe_transaction_error(ixs, &err)
+        )),
+        None => {
+            println!("Simulation succeeded. Transaction was not sent.");
+            Ok(())
+        }
+    }
+}
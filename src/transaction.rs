@@ -1,23 +1,186 @@
 use anyhow::{anyhow, Result};
-use retry::{delay::Exponential, retry};
-use solana_client::{rpc_client::RpcClient, rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig}};
+use clap::Args;
+use solana_client::{
+    client_error::{ClientError, ClientErrorKind},
+    rpc_client::RpcClient,
+    rpc_config::{RpcSendTransactionConfig, RpcSimulateTransactionConfig, RpcTransactionConfig},
+    rpc_request::{RpcError, RpcResponseErrorData},
+};
 use solana_program::instruction::Instruction;
 use solana_sdk::{
+    address_lookup_table::{state::AddressLookupTable, AddressLookupTableAccount},
+    clock::MAX_PROCESSING_AGE,
     commitment_config::CommitmentConfig,
+    compute_budget::{self, ComputeBudgetInstruction},
     hash::Hash,
+    message::{
+        v0::{self, LoadedAddresses, LoadedMessage},
+        Message, VersionedMessage,
+    },
+    nonce::state::{Data as NonceData, State as NonceState, Versions as NonceVersions},
     pubkey::Pubkey,
-    signature::{Keypair, Signature},
+    signature::Signature,
     signer::Signer,
-    transaction::Transaction,
+    system_instruction, system_program,
+    transaction::VersionedTransaction,
 };
 
+use crate::{
+    errors::describe_transaction_error,
+    offline::{absent_signers, sign_only_tx, TransactionEncoding},
+    setup::{read_signer, CliSigner},
+    simulation::simulate_tx,
+};
+
+use solana_transaction_status::UiTransactionEncoding;
+
 use std::{
     fmt::{self, Display, Formatter},
+    path::PathBuf,
     str::FromStr,
+    thread,
+    time::Duration,
 };
 
 pub const MAX_TX_SIZE: usize = 1232;
 pub const DEFAULT_CU: u64 = 15_000;
+pub const MAX_CU: u32 = 1_400_000;
+/// Accounts a transaction may lock, counting those loaded from lookup tables.
+pub const MAX_TX_ACCOUNTS: usize = 64;
+
+/// Transaction building options shared by every command that sends a transaction.
+#[derive(Args, Debug, Clone, Eq, PartialEq)]
+pub struct TransactionOptions {
+    /// Compute unit limit to request. Skips simulation when set
+    #[arg(long, global = true)]
+    pub compute_unit_limit: Option<u32>,
+
+    /// Safety margin, in percent, added on top of the simulated compute units
+    #[arg(long, global = true, default_value_t = 10)]
+    pub compute_margin: u32,
+
+    /// Upper bound, in micro-lamports per compute unit, for the priority fee
+    #[arg(long, global = true)]
+    pub max_priority_fee: Option<u64>,
+
+    /// Build and simulate the transaction without sending it
+    #[arg(long, global = true)]
+    pub dry_run: bool,
+
+    /// Address lookup table used to compile v0 transactions. Can be repeated
+    #[arg(long = "lookup-table", global = true)]
+    pub lookup_tables: Vec<Pubkey>,
+
+    /// Number of times a transaction is signed with a fresh blockhash and sent before giving up
+    #[arg(long, global = true, default_value_t = 3)]
+    pub send_attempts: u32,
+
+    /// Interval, in milliseconds, at which an unconfirmed transaction is rebroadcast
+    #[arg(long, global = true, default_value_t = 2_000)]
+    pub rebroadcast_interval: u64,
+
+    /// Increase, in percent, of the priority fee every time an expired transaction is re-signed
+    #[arg(long, global = true, default_value_t = 50)]
+    pub priority_fee_bump: u32,
+
+    /// Durable nonce account whose stored nonce is used instead of a recent blockhash
+    #[arg(long, global = true)]
+    pub nonce_account: Option<Pubkey>,
+
+    /// Keypair file of the nonce authority, or its public key with --sign-only. Defaults to the fee payer
+    #[arg(long, global = true, requires = "nonce_account")]
+    pub nonce_authority: Option<PathBuf>,
+
+    /// Sign with the available keys and print the transaction instead of sending it
+    #[arg(long, global = true)]
+    pub sign_only: bool,
+
+    /// Blockhash to sign with when using --sign-only. Defaults to the latest blockhash or the stored nonce
+    #[arg(long, global = true, requires = "sign_only")]
+    pub blockhash: Option<Hash>,
+
+    /// Encoding of transactions printed with --sign-only: base58 or base64
+    #[arg(long, global = true, default_value = "base64")]
+    pub tx_encoding: TransactionEncoding,
+
+    /// Sends progress messages to stderr, as the command prints machine
+    /// readable output on stdout, e.g. the `--vault` instruction bundle
+    #[arg(skip)]
+    pub progress_to_stderr: bool,
+}
+
+impl Default for TransactionOptions {
+    fn default() -> Self {
+        Self {
+            compute_unit_limit: None,
+            compute_margin: 10,
+            max_priority_fee: None,
+            dry_run: false,
+            lookup_tables: vec![],
+            send_attempts: 3,
+            rebroadcast_interval: 2_000,
+            priority_fee_bump: 50,
+            nonce_account: None,
+            nonce_authority: None,
+            sign_only: false,
+            blockhash: None,
+            tx_encoding: TransactionEncoding::Base64,
+            progress_to_stderr: false,
+        }
+    }
+}
+
+impl TransactionOptions {
+    /// Reads the `--nonce-authority` signer, if given.
+    pub fn read_nonce_authority(&self) -> Result<Option<Box<CliSigner>>> {
+        self.nonce_authority.as_deref().map(read_signer).transpose()
+    }
+
+    /// Prints a progress message. It goes to stderr with `--sign-only`, where
+    /// stdout only holds the exported transaction, or when stdout holds other
+    /// machine readable output.
+    pub fn progress(&self, message: impl Display) {
+        match self.sign_only || self.progress_to_stderr {
+            true => eprintln!("{message}"),
+            false => println!("{message}"),
+        }
+    }
+}
+
+/// Signers of a transaction paid by `payer`, adding the nonce authority when it is another key.
+pub fn transaction_signers<'a>(
+    payer: &'a CliSigner,
+    nonce_authority: Option<&'a CliSigner>,
+) -> Vec<&'a CliSigner> {
+    let mut signers = vec![payer];
+
+    if let Some(x) = nonce_authority {
+        if x.pubkey() != payer.pubkey() {
+            signers.push(x);
+        }
+    }
+
+    signers
+}
+
+/// Fetches and decodes an initialized durable nonce account.
+pub fn get_nonce_data(client: &RpcClient, nonce_account: &Pubkey) -> Result<NonceData> {
+    let account = client.get_account(nonce_account)?;
+
+    if account.owner != system_program::ID {
+        return Err(anyhow!("{nonce_account} is not a nonce account"));
+    }
+
+    let versions: NonceVersions = bincode::deserialize(&account.data)
+        .map_err(|_| anyhow!("{nonce_account} is not a nonce account"))?;
+
+    match versions.state() {
+        NonceState::Initialized(data) => Ok(data.clone()),
+        NonceState::Uninitialized => {
+            Err(anyhow!("Nonce account {nonce_account} is not initialized"))
+        }
+    }
+}
 
 #[derive(Debug, Default, Clone, Eq, PartialEq)]
 pub enum Priority {
@@ -27,6 +190,10 @@ pub enum Priority {
     Medium,
     High,
     Max,
+    /// 75th percentile of the recent prioritization fees paid for the transaction's writable accounts
+    Auto,
+    /// Given percentile (0-100) of the recent prioritization fees
+    Percentile(u8),
 }
 
 impl FromStr for Priority {
@@ -39,7 +206,13 @@ impl FromStr for Priority {
             "medium" => Ok(Self::Medium),
             "high" => Ok(Self::High),
             "max" => Ok(Self::Max),
-            _ => Err(anyhow!("Invalid priority".to_string())),
+            "auto" => Ok(Self::Auto),
+            x => match x.strip_prefix('p').map(u8::from_str) {
+                Some(Ok(percentile)) if percentile <= 100 => Ok(Self::Percentile(percentile)),
+                _ => Err(anyhow!(
+                    "Invalid priority. Expected none, low, medium, high, max, auto or p<0-100>"
+                )),
+            },
         }
     }
 }
@@ -52,146 +225,607 @@ impl Display for Priority {
             Self::Medium => write!(f, "Medium"),
             Self::High => write!(f, "High"),
             Self::Max => write!(f, "Max"),
+            Self::Auto => write!(f, "Auto"),
+            Self::Percentile(percentile) => write!(f, "p{percentile}"),
         }
     }
 }
 
-#[derive(Debug, Default, Clone, Eq, PartialEq)]
-pub enum Filter {
-    #[default]
-    All,
-    Group {
-        group_id: Pubkey
-    },
-    Creator {
-        creator_id: Pubkey
+pub fn get_priority_fee(priority: &Priority) -> u64 {
+    match priority {
+        Priority::None => 1_000,
+        Priority::Low => 50_000,
+        Priority::Medium => 200_000,
+        Priority::High => 1_000_000,
+        Priority::Max => 2_000_000,
+        // used when no recent fees are available
+        Priority::Auto | Priority::Percentile(_) => 200_000,
     }
 }
 
-impl FromStr for Filter {
-    type Err = anyhow::Error;
+/// Estimates the priority fee from the recent prioritization fees paid for the
+/// writable accounts of `ixs`, falling back to the static table when the
+/// priority is not dynamic or no fees are available.
+pub fn estimate_priority_fee(
+    client: &RpcClient,
+    priority: &Priority,
+    ixs: &[Instruction],
+    options: &TransactionOptions,
+) -> u64 {
+    let percentile = match priority {
+        Priority::Auto => Some(75),
+        Priority::Percentile(percentile) => Some(*percentile),
+        _ => None,
+    };
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut parts = s.split(":");
-        let filter_type = parts.next();
+    let fee = percentile
+        .and_then(|percentile| {
+            let mut accounts: Vec<Pubkey> = ixs
+                .iter()
+                .flat_map(|ix| ix.accounts.iter())
+                .filter(|meta| meta.is_writable)
+                .map(|meta| meta.pubkey)
+                .collect();
+            accounts.sort();
+            accounts.dedup();
+            // the RPC accepts at most 128 accounts
+            accounts.truncate(128);
+
+            let mut fees: Vec<u64> = client
+                .get_recent_prioritization_fees(&accounts)
+                .ok()?
+                .iter()
+                .map(|x| x.prioritization_fee)
+                .collect();
+
+            if fees.is_empty() {
+                return None;
+            }
+
+            fees.sort_unstable();
+            let index = (fees.len() - 1) * percentile as usize / 100;
+
+            Some(fees[index])
+        })
+        .unwrap_or_else(|| get_priority_fee(priority));
+
+    match options.max_priority_fee {
+        Some(max) => fee.min(max),
+        None => fee,
+    }
+}
 
-        match filter_type {
-            None => {
-                panic!("Invalid filter type. Filter form is <type>:<pubkey> where <type> is 'g' for group or 'a' for all (permits everything)")
+/// Prepends the compute budget instructions to `ixs`, preceded by the nonce
+/// advance when `options` use a durable nonce.
+///
+/// The compute unit limit is taken from `options` when set, otherwise it is
+/// simulated and padded with the configured margin.
+pub fn with_compute_budget(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    ixs: Vec<Instruction>,
+    lookup_tables: &[AddressLookupTableAccount],
+    priority: &Priority,
+    options: &TransactionOptions,
+) -> Result<Vec<Instruction>> {
+    // advancing the nonce must be the first instruction of the transaction
+    let nonce_ixs = match &options.nonce_account {
+        Some(nonce_account) => {
+            let authority = get_nonce_data(client, nonce_account)?.authority;
+
+            if !signers.iter().any(|x| x.pubkey() == authority) {
+                return Err(anyhow!(
+                    "Nonce account {nonce_account} is controlled by {authority}. Pass its keypair as --nonce-authority"
+                ));
             }
-            Some(x) => {
-                match x {
-                    "a" => {
-                        Ok(Filter::All)
-                    },
-                    "g" => {
-                        Ok(Filter::Group { group_id: Pubkey::from_str(parts.next().unwrap()).unwrap() })
-                    },
-                    _ => {
-                        panic!("Invalid filter type. Filter form is <type>:<pubkey> where <type> is 'g' for group")
-                    }
+
+            vec![system_instruction::advance_nonce_account(
+                nonce_account,
+                &authority,
+            )]
+        }
+        None => vec![],
+    };
+
+    let compute_units = match options.compute_unit_limit {
+        Some(limit) => limit,
+        None => {
+            // simulate with the maximum limit so that heavy instructions are not cut short
+            let mut simulated_ixs = nonce_ixs.clone();
+            simulated_ixs.push(ComputeBudgetInstruction::set_compute_unit_limit(MAX_CU));
+            simulated_ixs.extend(ixs.iter().cloned());
+
+            match get_compute_units(client, &simulated_ixs, lookup_tables, signers) {
+                Ok(units) => {
+                    let units = units.saturating_mul(100 + options.compute_margin as u64) / 100;
+                    units.min(MAX_CU as u64) as u32
                 }
+                // let the dry run report the failure with its logs
+                Err(_) if options.dry_run => MAX_CU,
+                Err(err) => return Err(err),
             }
         }
+    };
 
-        
-    }
+    let mut instructions = nonce_ixs;
+    instructions.extend([
+        ComputeBudgetInstruction::set_compute_unit_limit(compute_units),
+        ComputeBudgetInstruction::set_compute_unit_price(estimate_priority_fee(
+            client, priority, &ixs, options,
+        )),
+    ]);
+    instructions.extend(ixs);
+
+    Ok(instructions)
 }
 
-impl Display for Filter {
-    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
-        match self {
-            Self::All => write!(f, "a"),
-            Self::Group {group_id}=> write!(f, "g:{}", group_id),
-            Self::Creator { creator_id } => write!(f, "c:{}", creator_id),
-        }
+/// Fetches and decodes the address lookup tables at `addresses`.
+pub fn fetch_lookup_tables(
+    client: &RpcClient,
+    addresses: &[Pubkey],
+) -> Result<Vec<AddressLookupTableAccount>> {
+    addresses
+        .iter()
+        .map(|address| {
+            let data = client.get_account_data(address)?;
+            let table = AddressLookupTable::deserialize(&data)
+                .map_err(|_| anyhow!("{address} is not an address lookup table"))?;
+
+            Ok(AddressLookupTableAccount {
+                key: *address,
+                addresses: table.addresses.to_vec(),
+            })
+        })
+        .collect()
+}
+
+/// Compiles `ixs` into a message paid by `payer`.
+///
+/// A v0 message is compiled when lookup tables are given, a legacy one otherwise.
+pub fn compile_message(
+    payer: &Pubkey,
+    ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> Result<VersionedMessage> {
+    if lookup_tables.is_empty() {
+        return Ok(VersionedMessage::Legacy(Message::new_with_blockhash(
+            ixs,
+            Some(payer),
+            &blockhash,
+        )));
     }
+
+    Ok(VersionedMessage::V0(v0::Message::try_compile(
+        payer,
+        ixs,
+        lookup_tables,
+        blockhash,
+    )?))
 }
 
+/// Builds a transaction paid by the first signer and signed by all of them.
+pub fn build_transaction(
+    signers: &[&CliSigner],
+    ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    blockhash: Hash,
+) -> Result<VersionedTransaction> {
+    let message = compile_message(&signers[0].pubkey(), ixs, lookup_tables, blockhash)?;
+    let signers: Vec<&dyn Signer> = signers.iter().map(|x| *x as &dyn Signer).collect();
+
+    Ok(VersionedTransaction::try_new(message, signers.as_slice())?)
+}
 
+/// Lists every account of `message` with its writable and signer flags,
+/// resolving the addresses loaded from `lookup_tables`.
+pub fn message_accounts(
+    message: &VersionedMessage,
+    lookup_tables: &[AddressLookupTableAccount],
+) -> Result<Vec<(Pubkey, bool, bool)>> {
+    match message {
+        VersionedMessage::Legacy(message) => Ok(message
+            .account_keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (*key, message.is_writable(i), message.is_signer(i)))
+            .collect()),
+        VersionedMessage::V0(message) => {
+            let mut loaded = LoadedAddresses::default();
+
+            for lookup in &message.address_table_lookups {
+                let table = lookup_tables
+                    .iter()
+                    .find(|x| x.key == lookup.account_key)
+                    .ok_or_else(|| anyhow!("Unknown lookup table {}", lookup.account_key))?;
+
+                let resolve = |indexes: &[u8]| {
+                    indexes
+                        .iter()
+                        .map(|i| {
+                            table.addresses.get(*i as usize).copied().ok_or_else(|| {
+                                anyhow!("Index {i} out of range of lookup table {}", table.key)
+                            })
+                        })
+                        .collect::<Result<Vec<_>>>()
+                };
+
+                loaded.writable.extend(resolve(&lookup.writable_indexes)?);
+                loaded.readonly.extend(resolve(&lookup.readonly_indexes)?);
+            }
 
-pub fn get_priority_fee(priority: &Priority) -> u64 {
-    match priority {
-        Priority::None => 1_000,
-        Priority::Low => 50_000,
-        Priority::Medium => 200_000,
-        Priority::High => 1_000_000,
-        Priority::Max => 2_000_000,
+            let message = LoadedMessage::new(message.clone(), loaded);
+
+            Ok(message
+                .account_keys()
+                .iter()
+                .enumerate()
+                .map(|(i, key)| (*key, message.is_writable(i), message.is_signer(i)))
+                .collect())
+        }
     }
 }
 
 #[macro_export]
 macro_rules! transaction {
     ($client:expr, $signers:expr, $instructions:expr) => {
-        Transaction::new_signed_with_payer(
-            $instructions,
-            Some(&$signers[0].pubkey()),
+        $crate::transaction!($client, $signers, $instructions, &[])
+    };
+    ($client:expr, $signers:expr, $instructions:expr, $lookup_tables:expr) => {
+        $crate::transaction::build_transaction(
             $signers,
+            $instructions,
+            $lookup_tables,
             $client.get_latest_blockhash()?,
-        )
+        )?
     };
 }
 
-pub fn send_and_confirm_tx(
+/// Turns a failed send into a readable error.
+///
+/// Prints the program logs of the failed transaction, taken from the preflight
+/// simulation or fetched from the confirmed transaction, and decodes mule
+/// program errors.
+fn transaction_failure(
     client: &RpcClient,
-    signers: &[&Keypair],
+    signature: &Signature,
     ixs: &[Instruction],
-) -> Result<Signature> {
-    let tx = transaction!(client, signers, ixs);
+    err: ClientError,
+) -> anyhow::Error {
+    let logs = match err.kind() {
+        ClientErrorKind::RpcError(RpcError::RpcResponseError {
+            data: RpcResponseErrorData::SendTransactionPreflightFailure(result),
+            ..
+        }) => result.logs.clone(),
+        ClientErrorKind::TransactionError(_) => client
+            .get_transaction_with_config(
+                signature,
+                RpcTransactionConfig {
+                    encoding: Some(UiTransactionEncoding::Json),
+                    commitment: Some(CommitmentConfig::confirmed()),
+                    max_supported_transaction_version: Some(0),
+                },
+            )
+            .ok()
+            .and_then(|tx| tx.transaction.meta)
+            .and_then(|meta| meta.log_messages.into()),
+        _ => None,
+    };
+
+    if let Some(logs) = logs {
+        eprintln!("Program logs:");
+        for log in logs {
+            eprintln!("  {log}");
+        }
+    }
+
+    match err.get_transaction_error() {
+        Some(tx_err) => anyhow!(
+            "Transaction {signature} failed: {}",
+            describe_transaction_error(ixs, &tx_err)
+        ),
+        None => err.into(),
+    }
+}
+
+/// Returns the price set by `ix` if it is a `SetComputeUnitPrice` instruction.
+fn compute_unit_price(ix: &Instruction) -> Option<u64> {
+    let tag = ComputeBudgetInstruction::set_compute_unit_price(0).data[0];
+
+    if ix.program_id != compute_budget::id() || ix.data.first() != Some(&tag) {
+        return None;
+    }
+
+    ix.data
+        .get(1..9)
+        .map(|x| u64::from_le_bytes(x.try_into().unwrap()))
+}
+
+/// Raises the compute unit price of `ixs` by `options.priority_fee_bump` percent,
+/// within `options.max_priority_fee`. Returns the new price, if any is set.
+fn bump_priority_fee(ixs: &mut [Instruction], options: &TransactionOptions) -> Option<u64> {
+    let ix = ixs.iter_mut().find(|ix| compute_unit_price(ix).is_some())?;
+    let price = compute_unit_price(ix)?;
 
-    let signature = client.send_and_confirm_transaction(&tx)?;
+    let mut bumped = (price as u128 * (100 + options.priority_fee_bump as u128) / 100)
+        .min(u64::MAX as u128) as u64;
+    if let Some(max) = options.max_priority_fee {
+        bumped = bumped.min(max.max(price));
+    }
+
+    *ix = ComputeBudgetInstruction::set_compute_unit_price(bumped);
 
-    Ok(signature)
+    Some(bumped)
 }
 
-pub fn send_and_confirm_tx_with_spinner_with_config(
+/// Broadcasts the signed `tx`, then rebroadcasts it every `options.rebroadcast_interval`
+/// until it is confirmed or fails, or `is_expired` reports that it can no longer land.
+///
+/// Returns `None` when the transaction expired. `ixs` are only used to describe failures.
+pub fn broadcast_until_landed(
     client: &RpcClient,
-    signers: &[&Keypair],
+    tx: &VersionedTransaction,
     ixs: &[Instruction],
-    config: RpcSendTransactionConfig
-) -> Result<Signature> {
-    let tx = transaction!(client, signers, ixs);
+    options: &TransactionOptions,
+    mut is_expired: impl FnMut() -> Result<bool>,
+) -> Result<Option<Signature>> {
+    let signature = tx.signatures[0];
+    let interval = Duration::from_millis(options.rebroadcast_interval.max(100));
+
+    // preflight the first broadcast so that failures come back with their logs
+    client
+        .send_transaction_with_config(
+            tx,
+            RpcSendTransactionConfig {
+                max_retries: Some(0),
+                preflight_commitment: Some(client.commitment().commitment),
+                ..Default::default()
+            },
+        )
+        .map_err(|err| transaction_failure(client, &signature, ixs, err))?;
 
-    let signature = client.send_transaction_with_config(
-        &tx,
-        config
-    )?;
+    let rebroadcast_config = RpcSendTransactionConfig {
+        skip_preflight: true,
+        max_retries: Some(0),
+        ..Default::default()
+    };
+
+    loop {
+        thread::sleep(interval);
+
+        match client.get_signature_status_with_commitment(&signature, client.commitment())? {
+            Some(Ok(())) => return Ok(Some(signature)),
+            Some(Err(err)) => return Err(transaction_failure(client, &signature, ixs, err.into())),
+            None => {}
+        }
 
-    Ok(signature)
+        if is_expired()? {
+            break;
+        }
+
+        // the RPC node may have dropped it, send it again under the same signature
+        let _ = client.send_transaction_with_config(tx, rebroadcast_config);
+    }
+
+    // it may have landed between the last status check and the expiry
+    match client.get_signature_status_with_commitment(&signature, client.commitment())? {
+        Some(Ok(())) => Ok(Some(signature)),
+        Some(Err(err)) => Err(transaction_failure(client, &signature, ixs, err.into())),
+        None => Ok(None),
+    }
 }
 
-pub fn send_and_confirm_tx_with_spinner(
+/// Sends `ixs` until confirmed, tracking the expiry of the blockhash.
+///
+/// Each attempt signs the transaction with a fresh blockhash and rebroadcasts
+/// the same signature every `options.rebroadcast_interval` until it is
+/// confirmed or its blockhash expires. The next attempt then re-signs it with
+/// a bumped priority fee, up to `options.send_attempts` attempts.
+///
+/// With a durable nonce every attempt is signed with the same stored nonce, so
+/// at most one of them can land, and the next one starts after the usual
+/// blockhash lifetime. Once the nonce has moved on, including when it makes
+/// the preflight of a later attempt fail, the signatures of every attempt are
+/// checked and the landed one is returned instead of re-signing.
+///
+/// Progress is passed to `report` line by line.
+pub fn send_and_confirm_tx_with_resend(
     client: &RpcClient,
-    signers: &[&Keypair],
+    signers: &[&CliSigner],
     ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+    options: &TransactionOptions,
+    report: impl Fn(&str),
 ) -> Result<Signature> {
-    let tx = transaction!(client, signers, ixs);
+    let mut ixs = ixs.to_vec();
+    let attempts = options.send_attempts.max(1);
+
+    let nonce = match &options.nonce_account {
+        Some(nonce_account) => Some((
+            nonce_account,
+            get_nonce_data(client, nonce_account)?.blockhash(),
+        )),
+        None => None,
+    };
+
+    // signatures of every attempt, any of which may still land with a durable nonce
+    let mut sent = vec![];
+
+    for attempt in 1..=attempts {
+        let (blockhash, last_valid_block_height) = match nonce {
+            Some((_, nonce)) => (
+                nonce,
+                client.get_block_height()? + MAX_PROCESSING_AGE as u64,
+            ),
+            None => client.get_latest_blockhash_with_commitment(client.commitment())?,
+        };
+        let tx = build_transaction(signers, &ixs, lookup_tables, blockhash)?;
+        let signature = tx.signatures[0];
+
+        let absent = absent_signers(&tx);
+        if !absent.is_empty() {
+            return Err(anyhow!(
+                "Missing signature(s) of {}. Use --sign-only to export the transaction for offline signing",
+                absent
+                    .iter()
+                    .map(|x| x.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
 
-    let signature = client.send_and_confirm_transaction_with_spinner(&tx)?;
+        report(&format!(
+            "Attempt {attempt}/{attempts}: sending {signature}, valid until block height {last_valid_block_height}"
+        ));
 
-    Ok(signature)
+        sent.push(signature);
+
+        let landed = broadcast_until_landed(client, &tx, &ixs, options, || {
+            if client.get_block_height()? > last_valid_block_height {
+                return Ok(true);
+            }
+
+            // the nonce was used, by an earlier attempt or another transaction
+            match nonce {
+                Some((nonce_account, nonce)) => {
+                    Ok(get_nonce_data(client, nonce_account)?.blockhash() != nonce)
+                }
+                None => Ok(false),
+            }
+        });
+
+        let landed = match (landed, nonce) {
+            (Ok(landed), _) => landed,
+            // an earlier attempt may have used the nonce right before this preflight
+            (Err(err), Some((nonce_account, nonce)))
+                if get_nonce_data(client, nonce_account)?.blockhash() != nonce =>
+            {
+                match landed_signature(client, &sent, &ixs)? {
+                    Some(signature) => Some(signature),
+                    None => return Err(err),
+                }
+            }
+            (Err(err), _) => return Err(err),
+        };
+
+        if let Some(signature) = landed {
+            report(&format!(
+                "Attempt {attempt}/{attempts}: confirmed {signature}"
+            ));
+            return Ok(signature);
+        }
+
+        if let Some((nonce_account, nonce)) = nonce {
+            if get_nonce_data(client, nonce_account)?.blockhash() != nonce {
+                return match landed_signature(client, &sent, &ixs)? {
+                    Some(signature) => {
+                        report(&format!("Attempt {attempt}/{attempts}: confirmed {signature}"));
+                        Ok(signature)
+                    }
+                    None => Err(anyhow!(
+                        "Nonce account {nonce_account} was advanced by another transaction before any attempt landed"
+                    )),
+                };
+            }
+        }
+
+        report(&format!(
+            "Attempt {attempt}/{attempts}: {signature} expired"
+        ));
+
+        if attempt < attempts {
+            if let Some(price) = bump_priority_fee(&mut ixs, options) {
+                report(&format!(
+                    "Re-signing with a priority fee of {price} micro-lamports per compute unit"
+                ));
+            }
+        }
+    }
+
+    match nonce {
+        Some((nonce_account, _)) => Err(anyhow!(
+            "Transaction was not confirmed after {attempts} attempt(s). They can still land until nonce account {nonce_account} is advanced"
+        )),
+        None => Err(anyhow!(
+            "Transaction was not confirmed after {attempts} attempt(s)"
+        )),
+    }
 }
 
-pub fn send_and_confirm_tx_with_retries(
+/// Returns the signature among `signatures` that landed, if any, or its failure.
+fn landed_signature(
     client: &RpcClient,
-    signers: &[&Keypair],
+    signatures: &[Signature],
     ixs: &[Instruction],
-) -> Result<Signature> {
-    let tx = transaction!(client, signers, ixs);
+) -> Result<Option<Signature>> {
+    let statuses = client
+        .get_signature_statuses_with_history(signatures)?
+        .value;
+
+    for (signature, status) in signatures.iter().zip(statuses) {
+        match status.map(|x| x.status) {
+            Some(Ok(())) => return Ok(Some(*signature)),
+            Some(Err(err)) => return Err(transaction_failure(client, signature, ixs, err.into())),
+            None => {}
+        }
+    }
 
-    // Send tx with retries.
-    let res = retry(
-        Exponential::from_millis_with_factor(250, 2.0).take(3),
-        || client.send_and_confirm_transaction_with_spinner(&tx),
-    )?;
+    Ok(None)
+}
+
+/// Prepends the compute budget to `ixs`, then simulates the transaction on dry
+/// runs, exports it with `--sign-only`, or sends it until confirmed.
+///
+/// Returns the signature only when the transaction was sent.
+pub fn execute_ixs(
+    client: &RpcClient,
+    signers: &[&CliSigner],
+    ixs: Vec<Instruction>,
+    lookup_tables: &[AddressLookupTableAccount],
+    priority: &Priority,
+    options: &TransactionOptions,
+) -> Result<Option<Signature>> {
+    let instructions = with_compute_budget(client, signers, ixs, lookup_tables, priority, options)?;
+
+    if options.dry_run {
+        simulate_tx(client, signers, &instructions, lookup_tables)?;
+        return Ok(None);
+    }
 
-    Ok(res)
+    if options.sign_only {
+        sign_only_tx(client, signers, &instructions, lookup_tables, options)?;
+        return Ok(None);
+    }
+
+    send_and_confirm_tx_with_resend(
+        client,
+        signers,
+        &instructions,
+        lookup_tables,
+        options,
+        |line| println!("{line}"),
+    )
+    .map(Some)
 }
 
 pub fn pack_instructions<'a>(
     num_signers: u32,
     payer: &'a Pubkey,
     ixs: &'a [Instruction],
+) -> Vec<Vec<Instruction>> {
+    pack_instructions_with_prefix(num_signers, payer, &[], ixs, &[])
+}
+
+/// Packs `ixs` like [`pack_instructions`] while reserving room in every
+/// transaction for the `prefix` instructions (e.g. compute budget), which are
+/// not included in the returned batches. Transactions are sized as v0
+/// messages when `lookup_tables` are given, and kept within [`MAX_TX_ACCOUNTS`].
+pub fn pack_instructions_with_prefix<'a>(
+    num_signers: u32,
+    payer: &'a Pubkey,
+    prefix: &'a [Instruction],
+    ixs: &'a [Instruction],
+    lookup_tables: &'a [AddressLookupTableAccount],
 ) -> Vec<Vec<Instruction>> {
     // This contains the instructions that will be sent in each transaction.
     let mut transactions: Vec<Vec<Instruction>> = vec![];
@@ -203,17 +837,41 @@ pub fn pack_instructions<'a>(
 
     for ix in ixs {
         tx_instructions.push(ix.clone());
-        let tx = Transaction::new_with_payer(tx_instructions.as_slice(), Some(payer));
-        let tx_len = bincode::serialize(&tx).unwrap().len();
-
-        if tx_len > max_payload_size {
+        let candidate = [prefix, tx_instructions.as_slice()].concat();
+        // a message that cannot be compiled (e.g. too many accounts) is as good as too large
+        let fits = compile_message(payer, &candidate, lookup_tables, Hash::default())
+            .map(|message| {
+                let num_accounts = message.static_account_keys().len()
+                    + message.address_table_lookups().map_or(0, |lookups| {
+                        lookups
+                            .iter()
+                            .map(|x| x.writable_indexes.len() + x.readonly_indexes.len())
+                            .sum()
+                    });
+
+                let tx = VersionedTransaction {
+                    signatures: vec![
+                        Signature::default();
+                        message.header().num_required_signatures as usize
+                    ],
+                    message,
+                };
+
+                bincode::serialize(&tx).unwrap().len() <= max_payload_size
+                    && num_accounts <= MAX_TX_ACCOUNTS
+            })
+            .unwrap_or(false);
+
+        if !fits && tx_instructions.len() > 1 {
             let last_ix = tx_instructions.pop().unwrap();
             transactions.push(tx_instructions.clone());
             tx_instructions.clear();
             tx_instructions.push(last_ix);
         }
     }
-    transactions.push(tx_instructions);
+    if !tx_instructions.is_empty() {
+        transactions.push(tx_instructions);
+    }
 
     transactions
 }
@@ -221,7 +879,8 @@ pub fn pack_instructions<'a>(
 pub fn get_compute_units(
     client: &RpcClient,
     ixs: &[Instruction],
-    signers: &[&Keypair],
+    lookup_tables: &[AddressLookupTableAccount],
+    signers: &[&CliSigner],
 ) -> Result<u64> {
     let config = RpcSimulateTransactionConfig {
         sig_verify: false,
@@ -230,12 +889,12 @@ pub fn get_compute_units(
         ..Default::default()
     };
 
-    let tx = Transaction::new_signed_with_payer(
-        ixs,
-        Some(&signers[0].pubkey()),
+    let tx = build_transaction(
         signers,
+        ixs,
+        lookup_tables,
         Hash::new(Pubkey::default().as_ref()), // dummy value
-    );
+    )?;
 
     // This doesn't return an error if the simulation fails
     let sim_result = client.simulate_transaction_with_config(&tx, config)?;
@@ -243,7 +902,10 @@ pub fn get_compute_units(
     // it sets the error Option on the value in the Ok variant, so we check here
     // and return the error manually.
     if let Some(err) = sim_result.value.err {
-        return Err(err.into());
+        return Err(anyhow!(
+            "Simulation failed: {}",
+            describe_transaction_error(ixs, &err)
+        ));
     }
 
     // Otherwise, we can get the compute units from the simulation result
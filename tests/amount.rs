@@ -0,0 +1,39 @@
+use mule_cli::amount::{base_units_to_ui_amount, ui_amount_to_base_units};
+
+#[test]
+fn converts_ui_amounts_exactly() {
+    assert_eq!(ui_amount_to_base_units("1000.5", 9).unwrap(), 1_000_500_000_000);
+    assert_eq!(ui_amount_to_base_units("1", 6).unwrap(), 1_000_000);
+    assert_eq!(ui_amount_to_base_units(".5", 2).unwrap(), 50);
+    assert_eq!(ui_amount_to_base_units("42", 0).unwrap(), 42);
+    assert_eq!(ui_amount_to_base_units("0.000000001", 9).unwrap(), 1);
+}
+
+#[test]
+fn rejects_invalid_amounts() {
+    for amount in ["", ".", "-1", "1e9", "1.2.3", "abc"] {
+        assert!(ui_amount_to_base_units(amount, 9).is_err(), "{amount}");
+    }
+}
+
+#[test]
+fn rejects_extra_decimals() {
+    assert!(ui_amount_to_base_units("1.5", 0).is_err());
+    assert!(ui_amount_to_base_units("0.0000000001", 9).is_err());
+}
+
+#[test]
+fn rejects_overflow() {
+    assert_eq!(ui_amount_to_base_units("18446744073.709551615", 9).unwrap(), u64::MAX);
+    assert!(ui_amount_to_base_units("18446744073.709551616", 9).is_err());
+    assert!(ui_amount_to_base_units("18446744074", 9).is_err());
+    assert!(ui_amount_to_base_units("1", 20).is_err());
+}
+
+#[test]
+fn ui_and_base_units_round_trip() {
+    for (amount, decimals) in [(0, 9), (1, 9), (1_000_500_000_000, 9), (u64::MAX, 6), (7, 0)] {
+        let ui = base_units_to_ui_amount(amount, decimals);
+        assert_eq!(ui_amount_to_base_units(&ui, decimals).unwrap(), amount, "{ui}");
+    }
+}
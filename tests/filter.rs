@@ -0,0 +1,71 @@
+use std::str::FromStr;
+
+use libreplex_mule_client::types;
+use mule_cli::filter::Filter;
+use proptest::prelude::*;
+use solana_sdk::pubkey::Pubkey;
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn filter() -> impl Strategy<Value = Filter> {
+    prop_oneof![
+        Just(Filter(types::Filter::All)),
+        pubkey().prop_map(|group_id| Filter(types::Filter::Group { group_id })),
+        pubkey().prop_map(|creator_id| Filter(types::Filter::Creator { creator_id })),
+    ]
+}
+
+proptest! {
+    #[test]
+    fn display_and_from_str_round_trip(filter in filter()) {
+        prop_assert_eq!(Filter::from_str(&filter.to_string()).unwrap(), filter);
+    }
+
+    #[test]
+    fn from_str_is_total(s in "\\PC*") {
+        // never panics, and anything accepted displays back to an equivalent filter
+        if let Ok(filter) = Filter::from_str(&s) {
+            prop_assert_eq!(Filter::from_str(&filter.to_string()).unwrap(), filter);
+        }
+    }
+
+    #[test]
+    fn accepts_matches_filter_kind(
+        group in pubkey(),
+        other in pubkey(),
+        creators in prop::collection::vec(pubkey(), 0..5),
+    ) {
+        prop_assert!(Filter(types::Filter::All).accepts(None, &creators));
+        let group_filter = Filter(types::Filter::Group { group_id: group });
+        prop_assert!(group_filter.accepts(Some(group), &creators));
+        prop_assert_eq!(group_filter.accepts(Some(other), &creators), group == other);
+        prop_assert_eq!(
+            Filter(types::Filter::Creator { creator_id: other }).accepts(None, &creators),
+            creators.contains(&other)
+        );
+    }
+}
+
+#[test]
+fn long_forms_parse_to_short_forms() {
+    let key = Pubkey::new_unique();
+
+    assert_eq!(Filter::from_str("all").unwrap(), Filter(types::Filter::All));
+    assert_eq!(
+        Filter::from_str(&format!("group:{key}")).unwrap(),
+        Filter(types::Filter::Group { group_id: key })
+    );
+    assert_eq!(
+        Filter::from_str(&format!("creator:{key}")).unwrap(),
+        Filter(types::Filter::Creator { creator_id: key })
+    );
+}
+
+#[test]
+fn invalid_filters_are_errors() {
+    for filter in ["", "x", "g", "g:", "g:notapubkey", "c", "c:123", "a:11111111111111111111111111111111"] {
+        assert!(Filter::from_str(filter).is_err(), "{filter} should not parse");
+    }
+}
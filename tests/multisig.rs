@@ -0,0 +1,69 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use mule_cli::multisig::{
+    get_vault_address, vault_transaction_message, InstructionBundle, MultisigOptions,
+};
+use solana_sdk::{pubkey::Pubkey, system_instruction};
+
+#[test]
+fn serializes_vault_transaction_messages() {
+    let vault = get_vault_address(&Pubkey::new_unique(), 0);
+    let recipient = Pubkey::new_unique();
+
+    let ix = system_instruction::transfer(&vault, &recipient, 42);
+    let data = vault_transaction_message(&vault, &[ix.clone()]).unwrap();
+
+    // one writable signer, one writable non signer and the system program
+    assert_eq!(&data[..4], &[1, 1, 1, 3]);
+    assert_eq!(&data[4..36], vault.as_ref());
+    assert_eq!(&data[36..68], recipient.as_ref());
+
+    let ix_start = 4 + 3 * 32;
+    assert_eq!(data[ix_start], 1);
+    // program index, account indexes and u16 prefixed data
+    assert_eq!(&data[ix_start + 1..ix_start + 5], &[2, 2, 0, 1]);
+    assert_eq!(
+        &data[ix_start + 5..ix_start + 7],
+        &(ix.data.len() as u16).to_le_bytes()
+    );
+    assert_eq!(&data[ix_start + 7..data.len() - 1], ix.data.as_slice());
+    assert_eq!(data[data.len() - 1], 0);
+}
+
+#[test]
+fn instruction_bundles_print_as_json() {
+    let vault = Pubkey::new_unique();
+    let recipient = Pubkey::new_unique();
+    let ix = system_instruction::transfer(&vault, &recipient, 42);
+
+    let json =
+        serde_json::to_string_pretty(&InstructionBundle::new(&vault, &[ix.clone()])).unwrap();
+    let bundle: serde_json::Value = serde_json::from_str(&json).unwrap();
+
+    assert_eq!(bundle["authority"], vault.to_string());
+    assert_eq!(
+        bundle["instructions"][0]["program_id"],
+        ix.program_id.to_string()
+    );
+    assert_eq!(
+        bundle["instructions"][0]["accounts"][1]["pubkey"],
+        recipient.to_string()
+    );
+    assert_eq!(
+        STANDARD
+            .decode(bundle["instructions"][0]["data"].as_str().unwrap())
+            .unwrap(),
+        ix.data
+    );
+
+    // progress then goes to stderr, leaving stdout to the bundle
+    let options = MultisigOptions {
+        vault: Some(vault),
+        ..Default::default()
+    };
+    assert!(options.prints_bundle());
+    assert!(!MultisigOptions {
+        multisig: Some(Pubkey::new_unique()),
+        ..Default::default()
+    }
+    .prints_bundle());
+}
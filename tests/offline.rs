@@ -0,0 +1,108 @@
+use std::str::FromStr;
+
+use mule_cli::{
+    offline::{absent_signers, decode_transaction, TransactionEncoding, TransactionEnvelope},
+    setup::CliSigner,
+    transaction::build_transaction,
+};
+use solana_sdk::{
+    hash::Hash,
+    message::VersionedMessage,
+    signature::{Keypair, Signature},
+    signer::{null_signer::NullSigner, Signer},
+    system_instruction,
+    transaction::VersionedTransaction,
+};
+
+#[test]
+fn parses_transaction_encodings() {
+    assert_eq!(
+        TransactionEncoding::from_str("base58").unwrap(),
+        TransactionEncoding::Base58
+    );
+    assert_eq!(
+        TransactionEncoding::from_str("BASE64").unwrap(),
+        TransactionEncoding::Base64
+    );
+    assert!(TransactionEncoding::from_str("hex").is_err());
+}
+
+#[test]
+fn envelope_round_trips_partially_signed_transactions() {
+    let payer = Keypair::new();
+    let offline = NullSigner::new(&Keypair::new().pubkey());
+
+    let ix = system_instruction::transfer(&offline.pubkey(), &payer.pubkey(), 1);
+    let signers: Vec<&CliSigner> = vec![&payer, &offline];
+    let tx = build_transaction(&signers, &[ix], &[], Hash::new_unique()).unwrap();
+
+    assert_eq!(absent_signers(&tx), vec![offline.pubkey()]);
+
+    for encoding in [TransactionEncoding::Base58, TransactionEncoding::Base64] {
+        let envelope = TransactionEnvelope::new(&tx, encoding, None).unwrap();
+
+        assert_eq!(envelope.signers.len(), 2);
+        assert_eq!(envelope.signers[0].pubkey, payer.pubkey().to_string());
+        assert!(envelope.signers[0].signature.is_some());
+        assert!(envelope.signers[1].signature.is_none());
+
+        let decoded = envelope.transaction().unwrap();
+        assert_eq!(decoded, tx);
+        assert_ne!(decoded.signatures[0], Signature::default());
+    }
+}
+
+#[test]
+fn envelope_files_must_be_strict_json() {
+    let payer = Keypair::new();
+    let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+    let signers: Vec<&CliSigner> = vec![&payer];
+    let tx = build_transaction(&signers, &[ix], &[], Hash::new_unique()).unwrap();
+
+    let envelope = TransactionEnvelope::new(&tx, TransactionEncoding::Base64, None).unwrap();
+    let json = serde_json::to_string_pretty(&envelope).unwrap();
+
+    let path = std::env::temp_dir().join(format!("envelope-{}.json", payer.pubkey()));
+
+    std::fs::write(&path, &json).unwrap();
+    assert_eq!(
+        TransactionEnvelope::read(&path)
+            .unwrap()
+            .transaction()
+            .unwrap(),
+        tx
+    );
+
+    // progress output ahead of the envelope is not skipped
+    std::fs::write(&path, format!("Sending transaction\n{json}")).unwrap();
+    assert!(TransactionEnvelope::read(&path).is_err());
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn malformed_transactions_are_errors() {
+    let payer = Keypair::new();
+    let ix = system_instruction::transfer(&payer.pubkey(), &payer.pubkey(), 1);
+    let signers: Vec<&CliSigner> = vec![&payer];
+    let tx = build_transaction(&signers, &[ix], &[], Hash::new_unique()).unwrap();
+
+    let encode =
+        |tx: &VersionedTransaction| bs58::encode(bincode::serialize(tx).unwrap()).into_string();
+
+    assert_eq!(
+        decode_transaction(&encode(&tx), TransactionEncoding::Base58).unwrap(),
+        tx
+    );
+
+    let mut missing_signature = tx.clone();
+    missing_signature.signatures.clear();
+    assert!(decode_transaction(&encode(&missing_signature), TransactionEncoding::Base58).is_err());
+
+    let mut unknown_program = tx.clone();
+    match &mut unknown_program.message {
+        VersionedMessage::Legacy(message) => message.instructions[0].program_id_index = 42,
+        VersionedMessage::V0(message) => message.instructions[0].program_id_index = 42,
+    }
+    assert!(decode_transaction(&encode(&unknown_program), TransactionEncoding::Base58).is_err());
+}
@@ -0,0 +1,147 @@
+use mule_cli::{
+    commands::batch_entries,
+    transaction::{
+        compile_message, pack_instructions, pack_instructions_with_prefix, MAX_TX_ACCOUNTS,
+        MAX_TX_SIZE,
+    },
+};
+use solana_program::instruction::{AccountMeta, Instruction};
+use solana_sdk::{
+    address_lookup_table::AddressLookupTableAccount, hash::Hash, pubkey::Pubkey,
+    signature::Signature, transaction::VersionedTransaction,
+};
+
+fn ix_with_data(program_id: &Pubkey, len: usize) -> Instruction {
+    Instruction::new_with_bytes(*program_id, &vec![1; len], vec![])
+}
+
+fn ix_with_accounts(program_id: &Pubkey, accounts: &[Pubkey]) -> Instruction {
+    Instruction::new_with_bytes(
+        *program_id,
+        &[1],
+        accounts
+            .iter()
+            .map(|x| AccountMeta::new_readonly(*x, false))
+            .collect(),
+    )
+}
+
+fn tx_size(
+    payer: &Pubkey,
+    ixs: &[Instruction],
+    lookup_tables: &[AddressLookupTableAccount],
+) -> usize {
+    let message = compile_message(payer, ixs, lookup_tables, Hash::default()).unwrap();
+    let tx = VersionedTransaction {
+        signatures: vec![Signature::default(); message.header().num_required_signatures as usize],
+        message,
+    };
+
+    bincode::serialize(&tx).unwrap().len()
+}
+
+#[test]
+fn packs_instructions_within_the_size_limit() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let ixs: Vec<Instruction> = (0..10).map(|_| ix_with_data(&program_id, 300)).collect();
+
+    let batches = pack_instructions(1, &payer, &ixs);
+
+    assert!(batches.len() > 1);
+    assert_eq!(batches.concat(), ixs);
+    for batch in &batches {
+        assert!(tx_size(&payer, batch, &[]) <= MAX_TX_SIZE);
+    }
+}
+
+#[test]
+fn reserves_room_for_the_prefix() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let ixs: Vec<Instruction> = (0..10).map(|_| ix_with_data(&program_id, 300)).collect();
+    let prefix = vec![ix_with_data(&Pubkey::new_unique(), 400)];
+
+    let without_prefix = pack_instructions(1, &payer, &ixs);
+    let batches = pack_instructions_with_prefix(1, &payer, &prefix, &ixs, &[]);
+
+    // the prefix is reserved, not returned
+    assert_eq!(batches.concat(), ixs);
+    assert!(batches.len() > without_prefix.len());
+    for batch in &batches {
+        assert!(
+            tx_size(&payer, &[prefix.as_slice(), batch.as_slice()].concat(), &[]) <= MAX_TX_SIZE
+        );
+    }
+}
+
+#[test]
+fn caps_the_accounts_loaded_from_lookup_tables() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+
+    let accounts: Vec<Pubkey> = (0..120).map(|_| Pubkey::new_unique()).collect();
+    let lookup_tables = vec![AddressLookupTableAccount {
+        key: Pubkey::new_unique(),
+        addresses: accounts.clone(),
+    }];
+
+    let ixs: Vec<Instruction> = accounts
+        .chunks(10)
+        .map(|chunk| ix_with_accounts(&program_id, chunk))
+        .collect();
+
+    // the accounts fit in one transaction by size, but not by count
+    assert!(tx_size(&payer, &ixs, &lookup_tables) <= MAX_TX_SIZE);
+
+    let batches = pack_instructions_with_prefix(1, &payer, &[], &ixs, &lookup_tables);
+
+    assert!(batches.len() > 1);
+    assert_eq!(batches.concat(), ixs);
+    for batch in &batches {
+        // the payer and the program are static keys
+        let num_accounts = batch.iter().map(|x| x.accounts.len()).sum::<usize>() + 2;
+        assert!(num_accounts <= MAX_TX_ACCOUNTS);
+    }
+}
+
+#[test]
+fn oversized_instructions_get_their_own_batch() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+
+    let small = ix_with_data(&program_id, 10);
+    let oversized = ix_with_data(&program_id, MAX_TX_SIZE);
+    let ixs = vec![small.clone(), oversized.clone(), small.clone()];
+
+    let batches = pack_instructions_with_prefix(1, &payer, &[], &ixs, &[]);
+
+    assert_eq!(
+        batches,
+        vec![vec![small.clone()], vec![oversized], vec![small]]
+    );
+}
+
+#[test]
+fn maps_batches_back_to_entries() {
+    let payer = Pubkey::new_unique();
+    let program_id = Pubkey::new_unique();
+    let ixs: Vec<Instruction> = (0..6).map(|_| ix_with_data(&program_id, 300)).collect();
+
+    // entries 1 and 4 had no instruction built
+    let ix_entries = vec![0, 2, 3, 5, 6, 7];
+
+    let batches = pack_instructions(1, &payer, &ixs);
+    let entries = batch_entries(&batches, &ix_entries);
+
+    assert_eq!(entries.len(), batches.len());
+    assert_eq!(entries.concat(), ix_entries);
+    for (batch, entries) in batches.iter().zip(&entries) {
+        assert_eq!(batch.len(), entries.len());
+    }
+
+    assert_eq!(
+        batch_entries(&[vec![ixs[0].clone()], ixs[1..4].to_vec()], &[3, 5, 8, 9]),
+        vec![vec![3], vec![5, 8, 9]]
+    );
+}